@@ -0,0 +1,267 @@
+use futures::{StreamExt, stream};
+use rand::{SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use serde::Serialize;
+use std::{sync::Arc, time::Instant};
+
+use crate::{
+    Args, Solver,
+    clients::{Entry, VectorStore},
+    config::{Opening, OptimizerConfig},
+    errors::{KontekstoError, Result},
+    oracle::{CountingOracle, LocalOracle},
+    solver::{AnySolver, solve_with_restarts_observed},
+};
+
+/// metrics for a single game
+#[derive(Serialize, Debug, Clone)]
+pub struct GameReport {
+    pub target: String,
+    pub solved: bool,
+    /// words successfully scored by the oracle
+    pub guesses: usize,
+    /// every call made to the oracle, including failed ones
+    pub api_calls: usize,
+    pub best_word: String,
+    pub best_rank: u32,
+    pub wall_time_ms: u128,
+    /// why the game couldn't be played, e.g. the target is missing from the collection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl GameReport {
+    fn failed(target: &str, error: String, wall_time_ms: u128) -> Self {
+        Self {
+            target: target.to_owned(),
+            solved: false,
+            guesses: 0,
+            api_calls: 0,
+            best_word: String::new(),
+            best_rank: u32::MAX,
+            wall_time_ms,
+            error: Some(error),
+        }
+    }
+}
+
+/// aggregate metrics over all games of a run
+#[derive(Serialize, Debug, Clone)]
+pub struct Summary {
+    pub games: usize,
+    pub solved: usize,
+    /// games which couldn't be played, counted as unsolved
+    pub failed: usize,
+    pub solved_rate: f32,
+    pub mean_guesses_to_solve: Option<f32>,
+    pub median_guesses_to_solve: Option<usize>,
+    pub mean_api_calls: f32,
+    pub mean_best_rank: f32,
+    pub wall_time_ms: u128,
+}
+
+impl Summary {
+    fn from_games(games: &[GameReport], wall_time_ms: u128) -> Self {
+        let n = games.len().max(1) as f32;
+
+        // calls and ranks are only averaged over the games which were played
+        let played: Vec<&GameReport> = games.iter().filter(|g| g.error.is_none()).collect();
+        let n_played = played.len().max(1) as f32;
+
+        let mut to_solve: Vec<usize> = games
+            .iter()
+            .filter(|g| g.solved)
+            .map(|g| g.guesses)
+            .collect();
+        to_solve.sort();
+
+        let mean_guesses_to_solve = (!to_solve.is_empty())
+            .then(|| to_solve.iter().sum::<usize>() as f32 / to_solve.len() as f32);
+
+        Self {
+            games: games.len(),
+            solved: to_solve.len(),
+            failed: games.len() - played.len(),
+            solved_rate: to_solve.len() as f32 / n,
+            mean_guesses_to_solve,
+            median_guesses_to_solve: to_solve.get(to_solve.len() / 2).copied(),
+            mean_api_calls: played.iter().map(|g| g.api_calls).sum::<usize>() as f32 / n_played,
+            mean_best_rank: played.iter().map(|g| g.best_rank as f32).sum::<f32>() / n_played,
+            wall_time_ms,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BenchReport {
+    pub config: OptimizerConfig,
+    pub games: Vec<GameReport>,
+    pub summary: Summary,
+}
+
+impl BenchReport {
+    /// human readable table of per-game and aggregate metrics
+    pub fn table(&self) -> String {
+        let mut out = format!(
+            "{:<16} {:>6} {:>8} {:>9} {:<16} {:>6} {:>9}\n",
+            "target", "solved", "guesses", "api calls", "best", "rank", "time (ms)"
+        );
+        for g in self.games.iter() {
+            if let Some(error) = &g.error {
+                out.push_str(&format!("{:<16} failed: {}\n", g.target, error));
+                continue;
+            }
+            out.push_str(&format!(
+                "{:<16} {:>6} {:>8} {:>9} {:<16} {:>6} {:>9}\n",
+                g.target, g.solved, g.guesses, g.api_calls, g.best_word, g.best_rank, g.wall_time_ms
+            ));
+        }
+
+        let s = &self.summary;
+        let fmt_opt = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+        out.push_str(&format!(
+            "\nsolved {}/{} ({:.1}%), guesses to solve: mean {}, median {}\n",
+            s.solved,
+            s.games,
+            100.0 * s.solved_rate,
+            fmt_opt(s.mean_guesses_to_solve.map(|m| format!("{:.1}", m))),
+            fmt_opt(s.median_guesses_to_solve.map(|m| m.to_string())),
+        ));
+        if s.failed > 0 {
+            out.push_str(&format!("{} games could not be played\n", s.failed));
+        }
+        out.push_str(&format!(
+            "mean api calls {:.1}, mean best rank {:.1}, wall time {}ms\n",
+            s.mean_api_calls, s.mean_best_rank, s.wall_time_ms
+        ));
+        out
+    }
+}
+
+/// Runs the solver offline over many target words using a `LocalOracle`.
+///
+/// Every game is seeded from `--seed`, or a seed drawn once per bench, and the index of
+/// its target: runs with different optimizer configs open the same games the same way.
+#[derive(Clone)]
+pub struct Bench {
+    config: Args,
+    store: Arc<dyn VectorStore>,
+    entries: Arc<Vec<Entry>>,
+    seed: u64,
+}
+
+impl Bench {
+    pub async fn new(config: Args) -> Result<Self> {
+        let store = crate::setup_store(&config).await?;
+        Self::with_store(config, store).await
+    }
+
+    /// bench over an already loaded collection
+    pub async fn with_store(config: Args, store: Arc<dyn VectorStore>) -> Result<Self> {
+        let entries = store.get_all_entries().await?;
+        let seed = config.seed.unwrap_or_else(rand::random);
+
+        Ok(Self {
+            config,
+            store,
+            entries: Arc::new(entries),
+            seed,
+        })
+    }
+
+    /// base seed the seed of every game is derived from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// solve every target with `optimizer`, running up to `concurrency` games at once.
+    /// Each game is a task of its own, so on a multi-threaded runtime they're solved in
+    /// parallel. Games which fail are reported as such rather than failing the whole run
+    pub async fn run(
+        &self,
+        optimizer: OptimizerConfig,
+        targets: &[String],
+        concurrency: usize,
+    ) -> BenchReport {
+        let start = Instant::now();
+
+        let games: Vec<GameReport> = stream::iter(targets.iter().cloned().enumerate())
+            .map(|(i, target)| {
+                let bench = self.clone();
+                let seed = self.seed.wrapping_add(i as u64);
+                let game = tokio::spawn({
+                    let target = target.clone();
+                    async move {
+                        let start = Instant::now();
+                        match bench.run_game(optimizer, &target, seed).await {
+                            Ok(game) => game,
+                            Err(e) => GameReport::failed(
+                                &target,
+                                e.to_string(),
+                                start.elapsed().as_millis(),
+                            ),
+                        }
+                    }
+                });
+                async move {
+                    game.await
+                        .unwrap_or_else(|e| GameReport::failed(&target, e.to_string(), 0))
+                }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let summary = Summary::from_games(&games, start.elapsed().as_millis());
+
+        BenchReport {
+            config: optimizer,
+            games,
+            summary,
+        }
+    }
+
+    async fn run_game(
+        &self,
+        optimizer: OptimizerConfig,
+        target: &str,
+        seed: u64,
+    ) -> Result<GameReport> {
+        let start = Instant::now();
+
+        let mut config = self.config.clone();
+        config.optimizer_config = optimizer;
+        config.seed = Some(seed);
+
+        let oracle = CountingOracle::new(LocalOracle::from_entries(&self.entries, target)?);
+        let mut solver = AnySolver::new(Solver::with_oracle(config, self.store.clone(), oracle));
+
+        let seeds = match optimizer.opening {
+            Opening::Random => self.random_opening(seed, optimizer.max_retries)?,
+            Opening::Clusters => solver.generate_seeds(optimizer.max_retries).await?,
+        };
+        let (best_word, best_rank) = solve_with_restarts_observed(&mut solver, seeds, |_| {}).await;
+
+        Ok(GameReport {
+            target: target.to_owned(),
+            solved: best_rank == 0,
+            guesses: solver.oracle.successes(),
+            api_calls: solver.oracle.calls(),
+            best_word,
+            best_rank,
+            wall_time_ms: start.elapsed().as_millis(),
+            error: None,
+        })
+    }
+
+    /// random words to restart from, drawn from the game's seed rather than by the store
+    fn random_opening(&self, seed: u64, n: usize) -> Result<Vec<Vec<f32>>> {
+        if self.entries.is_empty() {
+            return Err(KontekstoError::EmptyCollection(self.store.collection().to_owned()));
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        Ok((0..n)
+            .filter_map(|_| self.entries.choose(&mut rng))
+            .map(|e| e.embedding.clone())
+            .collect())
+    }
+}
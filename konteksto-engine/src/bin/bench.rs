@@ -0,0 +1,67 @@
+use anyhow::Result;
+use clap::Parser;
+use konteksto_engine::{Args, bench::Bench, clients::Contexto};
+
+/// solve many games offline against a local oracle and report statistics
+#[derive(Parser, Debug)]
+struct Cli {
+    #[clap(flatten)]
+    config: Args,
+
+    /// target words to solve for, comma separated
+    #[clap(long, value_delimiter = ',')]
+    targets: Vec<String>,
+
+    /// file listing one target word per line
+    #[clap(long)]
+    targets_file: Option<String>,
+
+    /// contexto game ids whose answers are used as targets, comma separated
+    #[clap(long, value_delimiter = ',')]
+    game_ids: Vec<u32>,
+
+    /// number of games solved concurrently
+    #[clap(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// write the full report as json to this file
+    #[clap(long)]
+    json: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut cli = Cli::parse();
+    cli.config = cli.config.load_optimizer_file()?;
+
+    let mut targets = cli.targets.clone();
+    if let Some(file) = &cli.targets_file {
+        let words = std::fs::read_to_string(file)?;
+        targets.extend(words.lines().map(str::trim).filter(|w| !w.is_empty()).map(String::from));
+    }
+    for game_id in cli.game_ids.iter() {
+        // the answer is listed first in the game's top words
+        let top = Contexto::new(cli.config.lang, *game_id)
+            .with_client_config(cli.config.client_config.clone())?
+            .top()
+            .await?;
+        match top.into_iter().next() {
+            Some(answer) => targets.push(answer),
+            None => println!("WARN: no answer found for game {}", game_id),
+        }
+    }
+    anyhow::ensure!(!targets.is_empty(), "no targets to solve for");
+
+    let bench = Bench::new(cli.config.clone()).await?;
+    let report = bench
+        .run(cli.config.optimizer_config, &targets, cli.concurrency)
+        .await;
+
+    print!("{}", report.table());
+    if let Some(path) = cli.json {
+        std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+        println!("report written to {}", path);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use konteksto_engine::clients::ResponseCache;
+use konteksto_engine::config::Lang;
+
+/// inspect or clear the local cache of contexto responses
+#[derive(Parser, Debug)]
+struct Cli {
+    /// jsonl file caching contexto responses
+    #[clap(long, default_value = "./data/cache/contexto.jsonl")]
+    cache_file: String,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// list cached responses, ordered by game and rank
+    List(Filters),
+
+    /// number of cached responses per language and game
+    Stats(Filters),
+
+    /// remove cached responses; everything is dropped if no filter is given
+    Clear(Filters),
+}
+
+#[derive(Parser, Debug)]
+struct Filters {
+    #[clap(short, long)]
+    lang: Option<Lang>,
+
+    #[clap(long)]
+    game_id: Option<u32>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let cache = ResponseCache::open(&cli.cache_file)?;
+
+    match cli.command {
+        Command::List(Filters { lang, game_id }) => {
+            for e in cache.entries(lang, game_id) {
+                println!(
+                    "{:<6} {:>5} {:<16} {:<16} {:>6}",
+                    e.lang.to_string(),
+                    e.game_id,
+                    e.word,
+                    e.lemma,
+                    e.distance
+                );
+            }
+        }
+        Command::Stats(Filters { lang, game_id }) => {
+            let mut counts: Vec<((String, u32), usize)> = vec![];
+            for e in cache.entries(lang, game_id) {
+                let key = (e.lang.to_string(), e.game_id);
+                match counts.last_mut() {
+                    Some((k, n)) if *k == key => *n += 1,
+                    _ => counts.push((key, 1)),
+                }
+            }
+            for ((lang, game_id), n) in counts {
+                println!("{:<6} {:>5} {:>6}", lang, game_id, n);
+            }
+        }
+        Command::Clear(Filters { lang, game_id }) => {
+            let removed = cache.clear(lang, game_id)?;
+            println!("removed {} cached responses", removed);
+        }
+    }
+
+    Ok(())
+}
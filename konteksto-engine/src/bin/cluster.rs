@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::Parser;
+use konteksto_engine::{Args, clusters::Clusters, setup_store};
+use rand::{SeedableRng, rngs::StdRng};
+
+/// precompute k-means clusters of a collection for the cluster opening
+#[derive(Parser, Debug)]
+struct Cli {
+    #[clap(flatten)]
+    config: Args,
+
+    /// number of k-means iterations
+    #[clap(long, default_value_t = 25)]
+    iters: usize,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let Cli { config, iters } = Cli::parse();
+    let k = config.optimizer_config.clusters;
+
+    let store = setup_store(&config).await?;
+    let entries = store.get_all_entries().await?;
+    println!("clustering {} words into {} clusters", entries.len(), k);
+
+    let mut rng = StdRng::seed_from_u64(config.seed.unwrap_or_else(rand::random));
+    let clusters = Clusters::fit(&entries, k, iters, &mut rng)?;
+    for word in clusters.representatives.iter() {
+        println!("{}", word);
+    }
+
+    let file = Clusters::sidecar(store.collection(), k);
+    clusters.save(&file)?;
+    println!("clusters written to {}", file);
+
+    Ok(())
+}
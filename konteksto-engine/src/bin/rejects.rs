@@ -0,0 +1,58 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use konteksto_engine::clients::{RejectList, rejects::read_words};
+use konteksto_engine::config::Lang;
+
+/// inspect or share the words contexto refused to score
+#[derive(Parser, Debug)]
+struct Cli {
+    #[clap(short, long, default_value = "en")]
+    lang: Lang,
+
+    /// directory of the per-language reject lists
+    #[clap(long, default_value = "./data/rejects")]
+    rejects_dir: String,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// print the rejected words, one per line
+    List,
+
+    /// add the words of a file (one per line) to the list
+    Import { file: String },
+
+    /// write the list to a file, one word per line
+    Export { file: String },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rejects = RejectList::open(RejectList::file(&cli.rejects_dir, cli.lang))?;
+
+    match cli.command {
+        Command::List => {
+            for word in rejects.words() {
+                println!("{}", word);
+            }
+        }
+        Command::Import { file } => {
+            let added = rejects.extend(read_words(&file)?)?;
+            println!("imported {} new words, {} rejected in total", added, rejects.len());
+        }
+        Command::Export { file } => {
+            let words = rejects.words();
+            let mut out = words.join("\n");
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            std::fs::write(&file, out)?;
+            println!("exported {} words to {}", words.len(), file);
+        }
+    }
+
+    Ok(())
+}
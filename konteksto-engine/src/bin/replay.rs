@@ -0,0 +1,109 @@
+use anyhow::Result;
+use clap::Parser;
+use konteksto_engine::{
+    Args, Solver,
+    clients::RejectList,
+    oracle::ReplayOracle,
+    setup_store,
+    solver::{AnySolver, SolveEvent, print_event, solve_with_restarts_observed},
+    trace::Trace,
+};
+use std::sync::Arc;
+
+/// re-run a recorded solve against the ranks of its trace, without querying contexto
+#[derive(Parser, Debug)]
+struct Cli {
+    #[clap(flatten)]
+    config: Args,
+
+    /// trace written by `solve --trace`
+    #[clap(long)]
+    trace: String,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let Cli { mut config, trace } = Cli::parse();
+    let trace = Trace::load(&trace)?;
+
+    // the game, settings and rng seed come from the trace, the rest (vector store) from
+    // the cli
+    let recorded = trace
+        .config()
+        .ok_or_else(|| anyhow::anyhow!("trace has no config record"))?
+        .clone();
+    config.lang = recorded.lang;
+    config.game_id = recorded.game_id;
+    config.optimizer_config = recorded.optimizer;
+    config.seed = recorded.seed;
+    match &recorded.target {
+        Some(target) => println!("replaying offline game for '{}' ({})", target, recorded.lang),
+        None => println!("replaying game {} ({})", recorded.game_id, recorded.lang),
+    }
+    if recorded.seed.is_none() {
+        println!("WARN: trace has no rng seed, randomized strategies won't be reproduced");
+    }
+
+    let store = setup_store(&config).await?;
+    let oracle = ReplayOracle::new(trace.recording()).with_feedback(recorded.feedback);
+    let rejects = Arc::new(RejectList::in_memory(recorded.rejects));
+    let solver = Solver::with_oracle(config, store, oracle).with_rejects(rejects);
+    let mut solver = AnySolver::new(solver);
+
+    let mut restart = 0;
+    let mut moves = vec![];
+    let best = solve_with_restarts_observed(&mut solver, trace.seeds(), |event| {
+        match &event {
+            SolveEvent::Restart(i) => restart = *i,
+            SolveEvent::Guess { attempt, .. } => moves.push((restart, attempt.clone())),
+            SolveEvent::Checkpoint(_) | SolveEvent::Failed(_) => {}
+        }
+        print_event(event);
+    })
+    .await;
+
+    if let Some(word) = solver.oracle.divergence() {
+        anyhow::bail!(
+            "replay diverged after {} moves: '{}' was never scored in the recording",
+            moves.len(),
+            word
+        );
+    }
+
+    let recorded = trace.moves();
+    let matching = recorded
+        .iter()
+        .zip(moves.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    println!(
+        "\nbest: ({}, {}), {}/{} recorded moves reproduced",
+        best.0,
+        best.1,
+        matching,
+        recorded.len()
+    );
+
+    if matching < recorded.len() || moves.len() > recorded.len() {
+        let expected = recorded.get(matching);
+        let got = moves.get(matching);
+        anyhow::bail!(
+            "replay diverged at move {}: recorded {:?}, replayed {:?}",
+            matching + 1,
+            expected,
+            got
+        );
+    }
+
+    if let Some(best_recorded) = trace.best().filter(|b| *b != best) {
+        anyhow::bail!(
+            "replay diverged: recorded best ({}, {}), replayed ({}, {})",
+            best_recorded.0,
+            best_recorded.1,
+            best.0,
+            best.1
+        );
+    }
+
+    Ok(())
+}
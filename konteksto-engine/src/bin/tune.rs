@@ -0,0 +1,193 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use konteksto_engine::{
+    Args,
+    bench::{Bench, Summary},
+    config::OptimizerConfig,
+};
+use rand::seq::IndexedRandom;
+use std::cmp::Ordering;
+
+/// search over optimizer hyperparameters using offline benchmarks
+#[derive(Parser, Debug)]
+struct Cli {
+    #[clap(flatten)]
+    config: Args,
+
+    /// file listing one target word per line, used to evaluate every config
+    #[clap(long)]
+    targets_file: String,
+
+    #[clap(long, value_enum, default_value_t = Search::Grid)]
+    search: Search,
+
+    /// number of configs drawn from the search space in random search
+    #[clap(long, default_value_t = 20)]
+    samples: usize,
+
+    /// evaluate configs on a growing number of targets, keeping the best 1/eta each round
+    #[clap(long)]
+    halving: bool,
+
+    /// reduction factor for successive halving
+    #[clap(long, default_value_t = 3)]
+    eta: usize,
+
+    /// number of targets used in the first round of successive halving
+    #[clap(long, default_value_t = 5)]
+    min_targets: usize,
+
+    /// candidate values for `beta`, comma separated
+    #[clap(long, value_delimiter = ',', default_value = "0.3,0.5,0.7")]
+    betas: Vec<f32>,
+
+    /// candidate values for `margin`, comma separated
+    #[clap(long, value_delimiter = ',', default_value = "100,200,400")]
+    margins: Vec<u32>,
+
+    /// candidate values for `max_iters`, comma separated
+    #[clap(long, value_delimiter = ',', default_value = "50,100")]
+    iters: Vec<usize>,
+
+    /// candidate values for `neighbors`, comma separated
+    #[clap(long, value_delimiter = ',', default_value = "2,3,5")]
+    neighbor_counts: Vec<u64>,
+
+    /// candidate values for `max_retries`, comma separated
+    #[clap(long, value_delimiter = ',', default_value = "1,3")]
+    restarts: Vec<usize>,
+
+    /// number of games solved concurrently
+    #[clap(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// where to write the best config; defaults to `./data/optimizer/{lang}.json`
+    #[clap(long)]
+    out: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Search {
+    Grid,
+    Random,
+}
+
+impl Cli {
+    /// every combination of candidate values
+    fn grid(&self) -> Vec<OptimizerConfig> {
+        let mut configs = vec![];
+        for &beta in self.betas.iter() {
+            for &margin in self.margins.iter() {
+                for &max_iters in self.iters.iter() {
+                    for &neighbors in self.neighbor_counts.iter() {
+                        for &max_retries in self.restarts.iter() {
+                            let mut config = self.config.optimizer_config;
+                            config.beta = beta;
+                            config.margin = margin;
+                            config.max_iters = max_iters;
+                            config.neighbors = neighbors;
+                            config.max_retries = max_retries;
+                            configs.push(config);
+                        }
+                    }
+                }
+            }
+        }
+        configs
+    }
+
+    /// combinations drawn uniformly at random from the candidate values
+    fn random(&self) -> Vec<OptimizerConfig> {
+        let mut rng = rand::rng();
+        (0..self.samples)
+            .filter_map(|_| {
+                let mut config = self.config.optimizer_config;
+                config.beta = *self.betas.choose(&mut rng)?;
+                config.margin = *self.margins.choose(&mut rng)?;
+                config.max_iters = *self.iters.choose(&mut rng)?;
+                config.neighbors = *self.neighbor_counts.choose(&mut rng)?;
+                config.max_retries = *self.restarts.choose(&mut rng)?;
+                Some(config)
+            })
+            .collect()
+    }
+}
+
+/// higher solve rate first, then fewest api calls, then closest best guess
+fn compare(a: &Summary, b: &Summary) -> Ordering {
+    b.solved_rate
+        .total_cmp(&a.solved_rate)
+        .then(a.mean_api_calls.total_cmp(&b.mean_api_calls))
+        .then(a.mean_best_rank.total_cmp(&b.mean_best_rank))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let targets: Vec<String> = std::fs::read_to_string(&cli.targets_file)?
+        .lines()
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .map(String::from)
+        .collect();
+    anyhow::ensure!(!targets.is_empty(), "no targets to tune on");
+
+    let mut survivors = match cli.search {
+        Search::Grid => cli.grid(),
+        Search::Random => cli.random(),
+    };
+    anyhow::ensure!(!survivors.is_empty(), "empty search space");
+
+    // every config plays the same openings, see `Bench`
+    let bench = Bench::new(cli.config.clone()).await?;
+    println!("seeding games from {}, pass --seed to reproduce", bench.seed());
+
+    let mut budget = match cli.halving {
+        true => cli.min_targets.clamp(1, targets.len()),
+        false => targets.len(),
+    };
+
+    let results = loop {
+        println!(
+            "evaluating {} configs on {} targets",
+            survivors.len(),
+            budget
+        );
+
+        let mut results = vec![];
+        for optimizer in survivors.iter() {
+            let report = bench
+                .run(*optimizer, &targets[..budget], cli.concurrency)
+                .await;
+            println!(
+                "{:?} -> solved {:.1}%, mean api calls {:.1}, mean best rank {:.1}",
+                optimizer,
+                100.0 * report.summary.solved_rate,
+                report.summary.mean_api_calls,
+                report.summary.mean_best_rank
+            );
+            results.push((*optimizer, report.summary));
+        }
+        results.sort_by(|(_, a), (_, b)| compare(a, b));
+
+        if !cli.halving || results.len() == 1 || budget == targets.len() {
+            break results;
+        }
+
+        let keep = (results.len() / cli.eta.max(2)).max(1);
+        survivors = results.iter().take(keep).map(|(c, _)| *c).collect();
+        budget = (budget * cli.eta.max(2)).min(targets.len());
+    };
+
+    let (best, summary) = &results[0];
+    println!("\nbest: {:?}\n{:?}", best, summary);
+
+    let out = cli
+        .out
+        .unwrap_or_else(|| OptimizerConfig::tuned_file(cli.config.lang));
+    best.to_file(&out)?;
+    println!("config written to {}", out);
+
+    Ok(())
+}
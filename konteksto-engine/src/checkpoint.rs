@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Lang, file_name};
+use crate::solver::SolverState;
+
+/// Progress of an interrupted solve, saved to disk after every step
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Checkpoint {
+    pub lang: Lang,
+    pub game_id: u32,
+    /// set when solving offline against a known word
+    pub target: Option<String>,
+    /// set when solving a semantle clone, whose game is whatever the server plays
+    #[serde(default)]
+    pub semantle_url: Option<String>,
+    /// restart the solver was on (0-indexed)
+    pub restart: usize,
+    pub state: SolverState,
+}
+
+impl Checkpoint {
+    /// where the checkpoint of a game is kept unless told otherwise
+    pub fn default_file(
+        lang: Lang,
+        game_id: u32,
+        target: Option<&str>,
+        semantle_url: Option<&str>,
+    ) -> String {
+        match (target, semantle_url) {
+            (Some(target), _) => format!("./data/checkpoints/{}-local-{}.json", lang, target),
+            (None, Some(url)) => {
+                format!(
+                    "./data/checkpoints/{}-semantle-{}.json",
+                    lang,
+                    file_name(url)
+                )
+            }
+            (None, None) => format!("./data/checkpoints/{}-{}.json", lang, game_id),
+        }
+    }
+
+    pub fn load(file: &str) -> std::io::Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(file)?)?)
+    }
+
+    /// written to a temporary file first so an interrupt never leaves half a checkpoint
+    pub fn save(&self, file: &str) -> std::io::Result<()> {
+        if let Some(parent) = std::path::Path::new(file).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = format!("{}.tmp", file);
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(tmp, file)
+    }
+
+    /// whether this checkpoint was taken while solving the given game
+    pub fn is_for(
+        &self,
+        lang: Lang,
+        game_id: u32,
+        target: Option<&str>,
+        semantle_url: Option<&str>,
+    ) -> bool {
+        let url = |u: Option<&str>| u.map(|u| u.trim_end_matches('/').to_owned());
+        self.lang == lang
+            && self.game_id == game_id
+            && self.target.as_deref() == target
+            && url(self.semantle_url.as_deref()) == url(semantle_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OptimizerConfig;
+
+    #[test]
+    fn semantle_checkpoints_are_kept_per_server() {
+        let file = |url| Checkpoint::default_file(Lang::En, 1, None, Some(url));
+        assert_eq!(
+            file("https://semantle.example.com/"),
+            "./data/checkpoints/en-semantle-semantle-example-com.json"
+        );
+        assert_ne!(
+            file("https://semantle.example.com"),
+            file("http://localhost:3000")
+        );
+
+        let checkpoint = Checkpoint {
+            lang: Lang::En,
+            game_id: 1,
+            target: None,
+            semantle_url: Some("https://semantle.example.com/".to_owned()),
+            restart: 0,
+            state: SolverState::from_config(OptimizerConfig::default()),
+        };
+        assert!(checkpoint.is_for(Lang::En, 1, None, Some("https://semantle.example.com")));
+        assert!(!checkpoint.is_for(Lang::En, 1, None, Some("http://localhost:3000")));
+        assert!(!checkpoint.is_for(Lang::En, 1, None, None));
+    }
+}
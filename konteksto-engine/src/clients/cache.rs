@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::config::Lang;
+
+type Key = (Lang, u32, String);
+
+/// a single contexto response, as persisted on disk
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CachedResponse {
+    pub lang: Lang,
+    pub game_id: u32,
+    pub word: String,
+    pub distance: u32,
+    pub lemma: String,
+}
+
+impl CachedResponse {
+    fn key(&self) -> Key {
+        (self.lang, self.game_id, self.word.clone())
+    }
+}
+
+/// Append-only jsonl store of contexto responses keyed by `(lang, game_id, word)`.
+///
+/// The whole file is loaded in memory on open; new responses are appended as they
+/// come in so the cache survives restarts and crashes. Lines which don't parse, e.g. one
+/// cut short by a crash, are skipped.
+pub struct ResponseCache {
+    path: PathBuf,
+    entries: Mutex<Entries>,
+}
+
+/// responses along with the lemma of every word, which doesn't depend on the game
+#[derive(Default)]
+struct Entries {
+    responses: HashMap<Key, CachedResponse>,
+    lemmas: HashMap<(Lang, String), String>,
+}
+
+impl Entries {
+    fn insert(&mut self, entry: CachedResponse) {
+        self.lemmas
+            .insert((entry.lang, entry.word.clone()), entry.lemma.clone());
+        self.responses.insert(entry.key(), entry);
+    }
+}
+
+impl ResponseCache {
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut entries = Entries::default();
+        if path.exists() {
+            end_last_line(&path)?;
+            for line in read_lines(&path)? {
+                match serde_json::from_str::<CachedResponse>(&line) {
+                    Ok(entry) => entries.insert(entry),
+                    Err(e) => println!("WARN: skipping cached response '{}': {}", line, e),
+                }
+            }
+        }
+
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    pub fn get(&self, lang: Lang, game_id: u32, word: &str) -> Option<CachedResponse> {
+        let entries = self.entries.lock().unwrap();
+        entries.responses.get(&(lang, game_id, word.to_owned())).cloned()
+    }
+
+    /// lemma contexto gave `word` in any game of `lang`; lemmas don't depend on the game
+    pub fn lemma(&self, lang: Lang, word: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+        entries.lemmas.get(&(lang, word.to_owned())).cloned()
+    }
+
+    pub fn insert(&self, entry: CachedResponse) -> std::io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+
+        entries.insert(entry);
+        Ok(())
+    }
+
+    /// all cached responses matching the optional filters, ordered by game then distance
+    pub fn entries(&self, lang: Option<Lang>, game_id: Option<u32>) -> Vec<CachedResponse> {
+        let entries = self.entries.lock().unwrap();
+
+        let mut matches: Vec<_> = entries
+            .responses
+            .values()
+            .filter(|e| lang.is_none_or(|l| l == e.lang))
+            .filter(|e| game_id.is_none_or(|g| g == e.game_id))
+            .cloned()
+            .collect();
+
+        matches.sort_by_key(|e| (e.lang.to_string(), e.game_id, e.distance));
+        matches
+    }
+
+    /// drop cached responses matching the filters, returning how many were removed
+    pub fn clear(&self, lang: Option<Lang>, game_id: Option<u32>) -> std::io::Result<usize> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let before = entries.responses.len();
+        entries.responses.retain(|_, e| {
+            !(lang.is_none_or(|l| l == e.lang) && game_id.is_none_or(|g| g == e.game_id))
+        });
+        entries.lemmas = entries
+            .responses
+            .values()
+            .map(|e| ((e.lang, e.word.clone()), e.lemma.clone()))
+            .collect();
+
+        // rewrite the file with whatever is left
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = BufWriter::new(File::create(&self.path)?);
+        for entry in entries.responses.values() {
+            writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        }
+        file.flush()?;
+
+        Ok(before - entries.responses.len())
+    }
+}
+
+/// non-blank lines of a file, skipping the ones which aren't valid utf-8
+pub(crate) fn read_lines(path: &Path) -> std::io::Result<Vec<String>> {
+    Ok(std::fs::read(path)?
+        .split(|b| *b == b'\n')
+        .filter_map(|line| match std::str::from_utf8(line) {
+            Ok(line) => Some(line.trim().to_owned()),
+            Err(e) => {
+                println!("WARN: skipping line of {}: {}", path.display(), e);
+                None
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// give an append-only file whose last line was cut short, e.g. by a crash, its newline
+/// back so the next append starts on a line of its own
+pub(crate) fn end_last_line(path: &Path) -> std::io::Result<()> {
+    let bytes = std::fs::read(path)?;
+    if bytes.last().is_some_and(|b| *b != b'\n') {
+        let mut file = OpenOptions::new().append(true).open(path)?;
+        writeln!(file)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(word: &str, distance: u32) -> CachedResponse {
+        CachedResponse {
+            lang: Lang::En,
+            game_id: 1,
+            word: word.to_owned(),
+            distance,
+            lemma: word.to_owned(),
+        }
+    }
+
+    #[test]
+    fn open_skips_a_truncated_last_line() {
+        let path = std::env::temp_dir().join(format!("cache-{}.jsonl", uuid::Uuid::new_v4()));
+        let line = serde_json::to_string(&response("cat", 12)).unwrap();
+        let truncated = &serde_json::to_string(&response("dog", 40)).unwrap()[..20];
+        std::fs::write(&path, format!("{}\n{}", line, truncated)).unwrap();
+
+        let cache = ResponseCache::open(&path).unwrap();
+        assert_eq!(cache.get(Lang::En, 1, "cat"), Some(response("cat", 12)));
+        assert_eq!(cache.entries(None, None).len(), 1);
+
+        // later appends aren't glued to the truncated line
+        cache.insert(response("bird", 7)).unwrap();
+        let cache = ResponseCache::open(&path).unwrap();
+        assert_eq!(cache.get(Lang::En, 1, "bird"), Some(response("bird", 7)));
+        assert_eq!(cache.entries(None, None).len(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,7 +1,10 @@
+use reqwest::StatusCode;
 use serde::Deserialize;
-use crate::config::Lang;
+use std::sync::Arc;
 
-const CONTEXTO_API_URL: &'static str = "https://api.contexto.me/machado";
+use crate::clients::{ApiClient, CachedResponse, RejectList, ResponseCache};
+use crate::config::{ClientConfig, Lang};
+use crate::errors::{KontekstoError, Result};
 
 /// json schema of the GET /word endpoint
 #[allow(dead_code)]
@@ -12,38 +15,225 @@ struct ContextoPayload {
     pub word: String,
 }
 
+/// contexto's answer to a guess
+#[derive(Debug, Clone, PartialEq)]
+pub struct Played {
+    pub distance: u32,
+    /// form contexto normalized the guess to, e.g. "cat" for "cats"; guesses sharing a
+    /// lemma get the same distance
+    pub lemma: String,
+}
+
+/// json schema of the GET /top endpoint
+#[derive(Deserialize)]
+struct TopPayload {
+    pub words: Vec<String>,
+}
+
+/// "too common" in english, portuguese and spanish
+const COMMON_WORD_MESSAGES: [&str; 4] = ["common", "comum", "común", "comun"];
+
+/// "I don't know the word" in english, portuguese and spanish
+const UNKNOWN_WORD_MESSAGES: [&str; 5] =
+    ["don't know", "do not know", "não conheço", "nao conheco", "no conozco"];
+
+/// Why contexto refused to score `word` in game `game_id`, from the status and message
+/// of its response.
+///
+/// Only messages about the word itself are rejections of the word; any other client
+/// error, e.g. a bad game id or a forbidden request, says nothing about it
+fn rejected_word(word: &str, game_id: u32, status: StatusCode, message: &str) -> KontekstoError {
+    let lower = message.to_lowercase();
+    if status.is_client_error() {
+        if COMMON_WORD_MESSAGES.iter().any(|m| lower.contains(m)) {
+            return KontekstoError::CommonWord(word.to_owned());
+        }
+        if UNKNOWN_WORD_MESSAGES.iter().any(|m| lower.contains(m)) {
+            return KontekstoError::UnknownWord(word.to_owned());
+        }
+        if status == StatusCode::NOT_FOUND {
+            return KontekstoError::GameNotFound(game_id);
+        }
+    }
+    anyhow::anyhow!("contexto answered {} for '{}': {}", status, word, message).into()
+}
+
 /// A struct for making api calls to contexto
+///
+/// Clones share the same rate limiter, so every copy handed around (e.g. to the web
+/// handlers and the solver) counts towards the same limit.
 #[derive(Clone)]
 pub struct Contexto {
-    client: reqwest::Client,
+    http: ApiClient,
+    cache: Option<Arc<ResponseCache>>,
+    rejects: Option<Arc<RejectList>>,
     pub game_id: u32,
     pub lang: Lang,
 }
 impl Contexto {
     pub fn new(lang: Lang, game_id: u32) -> Self {
-        let client = reqwest::Client::new();
         Self {
             lang,
             game_id,
-            client,
-        }
-    }
-
-    pub async fn play(&self, word: &str) -> reqwest::Result<u32> {
-        let payload = self
-            .client
-            .get(format!(
-                "{}/{}/game/{}/{word}",
-                CONTEXTO_API_URL,
-                self.lang.to_string(),
-                self.game_id
-            ))
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<ContextoPayload>()
+            http: ApiClient::default(),
+            cache: None,
+            rejects: None,
+        }
+    }
+
+    /// pace, retry and time out requests following `settings`
+    pub fn with_client_config(mut self, settings: ClientConfig) -> Result<Self> {
+        self.http = ApiClient::new(settings)?;
+        Ok(self)
+    }
+
+    /// send requests through `http`, e.g. to share its rate limit with other clients
+    pub fn with_client(mut self, http: ApiClient) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// consult `cache` before hitting the api, and record every new response in it
+    pub fn with_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// skip words listed in `rejects` and add every word contexto refuses to it.
+    /// the list must be the one for this client's language
+    pub fn with_rejects(mut self, rejects: Arc<RejectList>) -> Self {
+        self.rejects = Some(rejects);
+        self
+    }
+
+    pub async fn play(&self, word: &str) -> Result<Played> {
+        if self.rejects.as_ref().is_some_and(|r| r.contains(word)) {
+            return Err(KontekstoError::UnknownWord(word.to_owned()));
+        }
+        if let Some(hit) = self
+            .cache
+            .as_ref()
+            .and_then(|c| c.get(self.lang, self.game_id, word))
+        {
+            return Ok(Played {
+                distance: hit.distance,
+                lemma: hit.lemma,
+            });
+        }
+
+        let url = format!(
+            "{}/{}/game/{}/{word}",
+            self.base_url(),
+            self.lang,
+            self.game_id
+        );
+        let payload: ContextoPayload = match self
+            .http
+            .get(&url, |status, message| {
+                rejected_word(word, self.game_id, status, message)
+            })
+            .await
+        {
+            Ok(payload) => payload,
+            Err(e) => {
+                // only words contexto said it doesn't know or won't count are kept for
+                // good; a bad game id or a refused request says nothing about the word
+                let rejects = self.rejects.as_ref().filter(|_| e.is_rejected_word());
+                if let Some(Err(e)) = rejects.map(|r| r.insert(word)) {
+                    println!("WARN: failed to record rejected word '{}': {}", word, e);
+                }
+                return Err(e);
+            }
+        };
+
+        if let Some(cache) = &self.cache {
+            let entry = CachedResponse {
+                lang: self.lang,
+                game_id: self.game_id,
+                word: word.to_owned(),
+                distance: payload.distance,
+                lemma: payload.lemma.clone(),
+            };
+            if let Err(e) = cache.insert(entry) {
+                println!("WARN: failed to cache response for '{}': {}", word, e);
+            }
+        }
+
+        Ok(Played {
+            distance: payload.distance,
+            lemma: payload.lemma,
+        })
+    }
+
+    /// lemma of `word` as learned from earlier responses, without calling the api
+    pub fn known_lemma(&self, word: &str) -> Option<String> {
+        self.cache.as_ref()?.lemma(self.lang, word)
+    }
+
+    /// words closest to the answer of the current game, the answer being first
+    pub async fn top(&self) -> Result<Vec<String>> {
+        let url = format!("{}/{}/top/{}", self.base_url(), self.lang, self.game_id);
+        let payload: TopPayload = self
+            .http
+            .get(&url, |status, message| match status {
+                StatusCode::NOT_FOUND => KontekstoError::GameNotFound(self.game_id),
+                _ => anyhow::anyhow!("contexto answered {}: {}", status, message).into(),
+            })
             .await?;
 
-        Ok(payload.distance)
+        Ok(payload.words)
+    }
+
+    fn base_url(&self) -> &str {
+        self.http.settings.contexto_url.trim_end_matches('/')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// messages contexto answers refused guesses with, in every language
+    const REJECTIONS: [(&str, &str, bool); 6] = [
+        ("en", "I'm sorry, I don't know this word", false),
+        ("en", "This word doesn't count, it's too common", true),
+        ("pt-br", "Desculpe, não conheço essa palavra", false),
+        ("pt-br", "Essa palavra não vale, é muito comum", true),
+        ("es", "Lo siento, no conozco esta palabra", false),
+        ("es", "Esta palabra no cuenta, es demasiado común", true),
+    ];
+
+    #[test]
+    fn refusals_of_the_word_are_rejections() {
+        for (lang, message, common) in REJECTIONS {
+            let e = rejected_word("word", 42, StatusCode::NOT_FOUND, message);
+            match common {
+                true => assert!(matches!(e, KontekstoError::CommonWord(_)), "{}: {}", lang, message),
+                false => assert!(matches!(e, KontekstoError::UnknownWord(_)), "{}: {}", lang, message),
+            }
+            assert!(e.is_rejected_word());
+            assert!(!e.is_transient());
+        }
+    }
+
+    #[test]
+    fn other_client_errors_are_not_rejections() {
+        let e = rejected_word("word", 42, StatusCode::NOT_FOUND, "Game not found");
+        assert!(matches!(e, KontekstoError::GameNotFound(42)));
+        assert!(!e.is_rejected_word());
+
+        let e = rejected_word("word", 42, StatusCode::FORBIDDEN, "");
+        assert!(!e.is_rejected_word());
+
+        // only client errors can be about the word
+        let e = rejected_word("word", 42, StatusCode::INTERNAL_SERVER_ERROR, "I don't know");
+        assert!(!e.is_rejected_word());
+    }
+
+    #[test]
+    fn outages_are_transient() {
+        assert!(KontekstoError::RateLimited.is_transient());
+        assert!(KontekstoError::Unavailable("timeout".into()).is_transient());
+        assert!(!KontekstoError::GameNotFound(42).is_transient());
     }
 }
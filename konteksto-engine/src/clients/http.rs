@@ -0,0 +1,225 @@
+use rand::Rng;
+use reqwest::{Response, StatusCode, header::RETRY_AFTER};
+use serde::{Deserialize, de::DeserializeOwned};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clients::RateLimiter;
+use crate::config::ClientConfig;
+use crate::errors::{KontekstoError, Result};
+
+/// json schema of error responses
+#[derive(Deserialize, Default)]
+struct ErrorPayload {
+    #[serde(default)]
+    pub error: String,
+}
+
+/// `Retry-After` delays are capped to this many times `backoff_ms`, so a server asking for
+/// hours doesn't stall a solve
+const MAX_RETRY_AFTER: u64 = 16;
+
+fn build_client(settings: &ClientConfig) -> Result<reqwest::Client> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(settings.timeout_ms))
+        .build()?;
+    Ok(client)
+}
+
+/// delay requested by the server through a `Retry-After: <seconds>` header
+fn retry_after(response: &Response) -> Option<Duration> {
+    let secs = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    secs.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Http client pacing, retrying and timing out requests to a game api.
+///
+/// Clones share the same rate limiter, so every copy handed around (e.g. to the web
+/// handlers and the solver) counts towards the same limit.
+#[derive(Clone)]
+pub struct ApiClient {
+    client: reqwest::Client,
+    limiter: Arc<RateLimiter>,
+    pub settings: ClientConfig,
+}
+
+impl Default for ApiClient {
+    /// client with the default settings; panics if the tls backend can't be initialized,
+    /// as `reqwest::Client::new` does
+    fn default() -> Self {
+        Self::new(ClientConfig::default()).expect("failed to build the default http client")
+    }
+}
+
+impl ApiClient {
+    pub fn new(settings: ClientConfig) -> Result<Self> {
+        Ok(Self {
+            client: build_client(&settings)?,
+            limiter: Arc::new(RateLimiter::new(settings.rate_limit)),
+            settings,
+        })
+    }
+
+    /// GET `url` within the rate limit, retrying rate limiting, server errors and
+    /// timeouts with exponential backoff and jitter. Other error responses are turned
+    /// into an error by `rejected` from their status and message
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        rejected: impl Fn(StatusCode, &str) -> KontekstoError,
+    ) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            self.limiter.wait().await;
+
+            let (e, delay) = match self.client.get(url).send().await {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(response.json::<T>().await?);
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let delay = retry_after(&response);
+                    let message = response.json::<ErrorPayload>().await.unwrap_or_default().error;
+
+                    let e = match status {
+                        StatusCode::TOO_MANY_REQUESTS => KontekstoError::RateLimited,
+                        s if s.is_server_error() => {
+                            KontekstoError::Unavailable(format!("{} {}", s, message))
+                        }
+                        _ => return Err(rejected(status, &message)),
+                    };
+                    (e, delay)
+                }
+                Err(e) if e.is_timeout() || e.is_connect() => {
+                    (KontekstoError::Unavailable(e.to_string()), None)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= self.settings.max_attempts {
+                return Err(e);
+            }
+            tokio::time::sleep(self.retry_delay(delay, attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// delay before retrying after `attempt` failed: what the server asked for, within
+    /// `MAX_RETRY_AFTER` times `backoff_ms`, or the exponential backoff
+    fn retry_delay(&self, requested: Option<Duration>, attempt: u32) -> Duration {
+        let cap = Duration::from_millis(self.settings.backoff_ms.saturating_mul(MAX_RETRY_AFTER));
+        match requested {
+            Some(delay) => delay.min(cap),
+            None => self.backoff(attempt),
+        }
+    }
+
+    /// `backoff_ms * 2^(attempt - 1)`, plus up to `backoff_ms` of jitter so clients
+    /// retrying together don't hit the api in lockstep
+    fn backoff(&self, attempt: u32) -> Duration {
+        let base = self.settings.backoff_ms;
+        let jitter = rand::rng().random_range(0..=base);
+        Duration::from_millis(base.saturating_mul(1 << (attempt - 1).min(10)) + jitter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn settings(max_attempts: u32, backoff_ms: u64) -> ClientConfig {
+        ClientConfig {
+            rate_limit: 0.0,
+            max_attempts,
+            backoff_ms,
+            timeout_ms: 1_000,
+            ..ClientConfig::default()
+        }
+    }
+
+    /// serve `responses` in turn, repeating the last one, and count the requests
+    async fn serve(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let count = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let i = count.fetch_add(1, Ordering::SeqCst);
+                let response = responses[i.min(responses.len() - 1)];
+
+                let mut buf = [0; 1024];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, requests)
+    }
+
+    fn rejected(status: StatusCode, message: &str) -> KontekstoError {
+        anyhow::anyhow!("{} {}", status, message).into()
+    }
+
+    const OK: &str = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 12\r\nconnection: close\r\n\r\n{\"error\":\"\"}";
+    const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\ncontent-type: application/json\r\ncontent-length: 16\r\nconnection: close\r\n\r\n{\"error\":\"nope\"}";
+    const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+    const RATE_LIMITED: &str = "HTTP/1.1 429 Too Many Requests\r\nretry-after: 86400\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+
+    #[test]
+    fn backoff_doubles_after_every_attempt() {
+        let client = ApiClient::new(settings(4, 100)).unwrap();
+        for (attempt, base) in [(1, 100), (2, 200), (3, 400), (4, 800)] {
+            let delay = client.backoff(attempt).as_millis() as u64;
+            assert!((base..=base + 100).contains(&delay), "attempt {}: {}ms", attempt, delay);
+        }
+    }
+
+    #[test]
+    fn retry_after_is_capped() {
+        let client = ApiClient::new(settings(4, 100)).unwrap();
+        let day = Some(Duration::from_secs(86_400));
+        assert_eq!(client.retry_delay(day, 1), Duration::from_millis(1_600));
+        let second = Some(Duration::from_secs(1));
+        assert_eq!(client.retry_delay(second, 1), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let (url, requests) = serve(vec![NOT_FOUND]).await;
+        let client = ApiClient::new(settings(4, 1)).unwrap();
+
+        let res = client.get::<ErrorPayload>(&url, rejected).await;
+        assert!(matches!(res, Err(e) if e.to_string().contains("nope")));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried_up_to_max_attempts() {
+        let (url, requests) = serve(vec![UNAVAILABLE]).await;
+        let client = ApiClient::new(settings(3, 1)).unwrap();
+
+        let res = client.get::<ErrorPayload>(&url, rejected).await;
+        assert!(matches!(res, Err(KontekstoError::Unavailable(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn long_retry_after_does_not_stall() {
+        let (url, requests) = serve(vec![RATE_LIMITED, OK]).await;
+        let client = ApiClient::new(settings(3, 1)).unwrap();
+
+        let res = tokio::time::timeout(
+            Duration::from_secs(5),
+            client.get::<ErrorPayload>(&url, rejected),
+        )
+        .await
+        .expect("retry-after wasn't capped");
+        assert!(res.is_ok());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
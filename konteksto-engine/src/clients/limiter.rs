@@ -0,0 +1,60 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Spaces out requests so no more than `per_second` start every second.
+///
+/// Each caller reserves the next free slot and sleeps until it comes up, so concurrent
+/// requests (e.g. from `join_all`) queue up instead of bursting.
+pub struct RateLimiter {
+    interval: Option<Duration>,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// `per_second <= 0` disables the limit
+    pub fn new(per_second: f64) -> Self {
+        let interval = (per_second > 0.0).then(|| Duration::from_secs_f64(1.0 / per_second));
+        Self {
+            interval,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub async fn wait(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        let slot = {
+            let mut next = self.next.lock().unwrap();
+            let slot = (*next).max(Instant::now());
+            *next = slot + interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn requests_are_spaced_out() {
+        let limiter = RateLimiter::new(5.0);
+        let start = Instant::now();
+
+        futures::future::join_all((0..6).map(|_| limiter.wait())).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_disables_the_limit() {
+        let limiter = RateLimiter::new(0.0);
+        let start = Instant::now();
+
+        futures::future::join_all((0..6).map(|_| limiter.wait())).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+use ndarray::{Array1, Array2};
+use rand::seq::IndexedRandom;
+use std::collections::{HashMap, HashSet};
+
+use crate::clients::{Entry, VectorStore};
+use crate::errors::{KontekstoError, Result};
+use crate::oracle::{normalize, unit_rows};
+
+/// An in-process vector store doing brute force cosine search.
+///
+/// Our vocabularies are only ~25k words so a full scan per query is cheap, and this
+/// lets the engine run without a qdrant server.
+pub struct MemoryStore {
+    pub collection: String,
+    words: Vec<String>,
+    index: HashMap<String, usize>,
+    /// unit-norm embeddings, one row per word
+    embeds: Array2<f32>,
+}
+
+impl MemoryStore {
+    /// fails if the entries don't all have the same dimension
+    pub fn new(collection: &str, entries: Vec<Entry>) -> Result<Self> {
+        let embeds = unit_rows(&entries)?;
+
+        let words: Vec<String> = entries.into_iter().map(|e| e.word).collect();
+        let index = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w.clone(), i))
+            .collect();
+
+        Ok(Self {
+            collection: collection.to_owned(),
+            words,
+            index,
+            embeds,
+        })
+    }
+
+    /// load a collection from the jsonl dump written by `konteksto-builder`
+    pub fn from_dump(file: &str, collection: &str) -> Result<Self> {
+        let entries = Entry::read_from_dump(file)?;
+        Self::new(collection, entries)
+    }
+
+    fn entry(&self, i: usize) -> Entry {
+        Entry {
+            word: self.words[i].clone(),
+            embedding: self.embeds.row(i).to_vec(),
+        }
+    }
+}
+
+#[async_trait]
+impl VectorStore for MemoryStore {
+    fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    async fn get_random_vecs(&self, how_many: u64) -> Result<Vec<Vec<f32>>> {
+        let indices: Vec<usize> = (0..self.words.len()).collect();
+        let vecs = indices
+            .choose_multiple(&mut rand::rng(), how_many as usize)
+            .map(|&i| self.embeds.row(i).to_vec())
+            .collect();
+        Ok(vecs)
+    }
+
+    async fn get_neighbors(
+        &self,
+        embedding: Vec<f32>,
+        how_many: u64,
+        exclude: &[String],
+    ) -> Result<Vec<Entry>> {
+        let exclude: HashSet<&str> = exclude.iter().map(String::as_str).collect();
+        let sims = self.embeds.dot(&normalize(Array1::from_vec(embedding)));
+
+        let mut candidates: Vec<(usize, f32)> = sims
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !exclude.contains(self.words[*i].as_str()))
+            .map(|(i, s)| (i, *s))
+            .collect();
+
+        let how_many = (how_many as usize).min(candidates.len());
+        if how_many == 0 {
+            return Ok(vec![]);
+        }
+        candidates.select_nth_unstable_by(how_many - 1, |a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(how_many);
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        Ok(candidates.into_iter().map(|(i, _)| self.entry(i)).collect())
+    }
+
+    async fn get_embedding(&self, word: String) -> Option<Vec<f32>> {
+        self.index.get(&word).map(|&i| self.embeds.row(i).to_vec())
+    }
+
+    async fn get_word(&self, embedding: Vec<f32>) -> Result<String> {
+        let nearest = self.get_neighbors(embedding, 1, &[]).await?;
+        nearest
+            .into_iter()
+            .next()
+            .map(|e| e.word)
+            .ok_or_else(|| KontekstoError::EmptyCollection(self.collection.clone()))
+    }
+
+    async fn count_points(&self) -> Option<u64> {
+        Some(self.words.len() as u64)
+    }
+
+    async fn get_all_entries(&self) -> Result<Vec<Entry>> {
+        Ok((0..self.words.len()).map(|i| self.entry(i)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, embedding: &[f32]) -> Entry {
+        Entry {
+            word: word.to_owned(),
+            embedding: embedding.to_vec(),
+        }
+    }
+
+    fn store() -> MemoryStore {
+        let entries = vec![
+            entry("north", &[0.0, 1.0]),
+            entry("north-east", &[1.0, 1.0]),
+            entry("east", &[2.0, 0.0]),
+            entry("south", &[0.0, -1.0]),
+        ];
+        MemoryStore::new("test", entries).unwrap()
+    }
+
+    #[tokio::test]
+    async fn neighbors_skip_excluded_words() {
+        let store = store();
+        let words =
+            |entries: Vec<Entry>| -> Vec<String> { entries.into_iter().map(|e| e.word).collect() };
+
+        let nearest = store.get_neighbors(vec![0.0, 3.0], 2, &[]).await.unwrap();
+        assert_eq!(words(nearest), ["north", "north-east"]);
+
+        let exclude = ["north".to_owned()];
+        let nearest = store
+            .get_neighbors(vec![0.0, 3.0], 2, &exclude)
+            .await
+            .unwrap();
+        assert_eq!(words(nearest), ["north-east", "east"]);
+
+        // asking for more than is left returns what's left
+        let exclude = ["north".to_owned(), "east".to_owned(), "south".to_owned()];
+        let nearest = store
+            .get_neighbors(vec![0.0, 3.0], 5, &exclude)
+            .await
+            .unwrap();
+        assert_eq!(words(nearest), ["north-east"]);
+    }
+
+    #[tokio::test]
+    async fn embeddings_are_unit_normed() {
+        let store = store();
+        let east = store.get_embedding("east".to_owned()).await.unwrap();
+        assert_eq!(east, [1.0, 0.0]);
+        assert_eq!(store.get_word(vec![5.0, 0.1]).await.unwrap(), "east");
+    }
+
+    #[test]
+    fn entries_of_another_dimension_are_refused() {
+        let entries = vec![entry("north", &[0.0, 1.0]), entry("up", &[0.0, 0.0, 1.0])];
+        let err = MemoryStore::new("test", entries).err().unwrap();
+        assert_eq!(err.to_string(), "line 2: 'up' has 3 dimensions, expected 2");
+    }
+}
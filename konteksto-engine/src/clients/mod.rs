@@ -1,5 +1,21 @@
+#[cfg(feature = "qdrant")]
 pub mod qdrant;
+pub mod memory;
+pub mod store;
 pub mod contexto;
+pub mod http;
+pub mod semantle;
+pub mod cache;
+pub mod limiter;
+pub mod rejects;
 
-pub use qdrant::{Qdrnt, Entry};
-pub use contexto::Contexto;
+#[cfg(feature = "qdrant")]
+pub use qdrant::Qdrnt;
+pub use memory::MemoryStore;
+pub use store::{Entry, VectorStore};
+pub use contexto::{Contexto, Played};
+pub use http::ApiClient;
+pub use semantle::Semantle;
+pub use cache::{CachedResponse, ResponseCache};
+pub use limiter::RateLimiter;
+pub use rejects::RejectList;
@@ -1,42 +1,25 @@
-use anyhow::Result;
 use qdrant_client::{
     Payload, Qdrant,
     qdrant::{
         Condition, CountPointsBuilder, CreateCollectionBuilder, Datatype,
         Distance, Filter, PointStruct, Query, QueryPointsBuilder, QueryResponse,
-        RecommendInputBuilder, Sample, ScoredPoint, ScrollPointsBuilder, UpsertPointsBuilder,
-        VectorParamsBuilder, vectors_output::VectorsOptions,
+        RecommendInputBuilder, Sample, ScrollPointsBuilder, UpsertPointsBuilder,
+        VectorParamsBuilder, VectorsOutput, vectors_output::VectorsOptions,
     },
 };
-use serde::Deserialize;
+use async_trait::async_trait;
 use serde_json::json;
 use std::ops::Deref;
 use uuid::Uuid;
 
 use crate::Args;
+use crate::clients::{Entry, VectorStore};
+use crate::errors::{KontekstoError, Result};
 
-/// jsonl schema from python dump
-#[derive(Deserialize, Debug)]
-pub struct Entry {
-    pub word: String,
-    pub embedding: Vec<f32>,
-}
-impl Entry {
-    pub fn read_from_dump(file: &str) -> Result<Vec<Self>> {
-        let entries = std::fs::read_to_string(file)?
-            .lines()
-            .into_iter()
-            .map(|l| serde_json::from_str(l))
-            .collect::<Result<_, _>>()?;
-
-        Ok(entries)
-    }
-}
-
-impl Into<PointStruct> for Entry {
-    fn into(self) -> PointStruct {
-        let payload: Payload = json!({"word": self.word}).try_into().unwrap();
-        PointStruct::new(Uuid::new_v4().to_string(), self.embedding, payload)
+impl From<Entry> for PointStruct {
+    fn from(entry: Entry) -> Self {
+        let payload: Payload = json!({"word": entry.word}).try_into().unwrap();
+        PointStruct::new(Uuid::new_v4().to_string(), entry.embedding, payload)
     }
 }
 
@@ -54,28 +37,25 @@ impl Deref for Qdrnt {
     }
 }
 
-// util for points storing a single embedding
-pub fn get_inner_vec(v: &ScoredPoint) -> Option<Vec<f32>> {
-    v.vectors.as_ref().map(|inner| match inner.vectors_options {
-        Some(VectorsOptions::Vector(ref w)) => w.data.clone(),
-        _ => unreachable!("we're not using sparse vecs!"),
-    })
+// util for points storing a single embedding, none for sparse or named vectors
+pub fn get_inner_vec(vectors: Option<&VectorsOutput>) -> Option<Vec<f32>> {
+    match vectors?.vectors_options {
+        Some(VectorsOptions::Vector(ref w)) => Some(w.data.clone()),
+        _ => None,
+    }
 }
 
+/// points missing their word or vector are skipped
 pub fn get_neighbors_from_response(response: &QueryResponse) -> Vec<Entry> {
-    let words = response
+    response
         .result
         .iter()
-        .filter_map(|v| v.payload.get("word"))
-        .map(|i| i.as_str().unwrap());
-
-    let embeds = response.result.iter().filter_map(get_inner_vec);
-
-    words
-        .zip(embeds)
-        .map(|(w, e)| Entry {
-            word: w.to_string(),
-            embedding: e,
+        .filter_map(|v| {
+            let word = v.payload.get("word")?.as_str()?;
+            Some(Entry {
+                word: word.to_owned(),
+                embedding: get_inner_vec(v.vectors.as_ref())?,
+            })
         })
         .collect()
 }
@@ -92,11 +72,16 @@ impl Qdrnt {
     pub async fn create_from_dump(&self, file: &str, collection: Option<&str>) -> Result<()> {
         let collection = collection.unwrap_or(&self.collection);
         let entries = Entry::read_from_dump(file)?;
+        let dim = entries
+            .first()
+            .ok_or_else(|| KontekstoError::EmptyCollection(collection.to_owned()))?
+            .embedding
+            .len();
 
         // create collection
         self.create_collection(
             CreateCollectionBuilder::new(collection).vectors_config(
-                VectorParamsBuilder::new(entries[0].embedding.len() as u64, Distance::Cosine)
+                VectorParamsBuilder::new(dim as u64, Distance::Cosine)
                     .datatype(Datatype::Float32),
             ),
         )
@@ -110,7 +95,30 @@ impl Qdrnt {
         Ok(())
     }
 
-    pub async fn get_random_vecs(&self, how_many: u64) -> Result<Vec<Vec<f32>>> {
+    pub async fn context_search(&self, positive_context: Vec<Vec<f32>>, n: u64) -> Result<Vec<Entry>>{
+
+        let mut context = RecommendInputBuilder::default();
+        for example in positive_context{
+            context = context.add_positive(example);
+        }
+
+        let response = self.query(
+            QueryPointsBuilder::new("{collection_name}")
+                .query(context.build())
+                .limit(n),
+        ).await?;
+
+        Ok(get_neighbors_from_response(&response))
+    }
+}
+
+#[async_trait]
+impl VectorStore for Qdrnt {
+    fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    async fn get_random_vecs(&self, how_many: u64) -> Result<Vec<Vec<f32>>> {
         let res = self
             .query(
                 QueryPointsBuilder::new(&self.collection)
@@ -120,11 +128,40 @@ impl Qdrnt {
             )
             .await?;
 
-        let vectors: Vec<Vec<f32>> = res.result.iter().filter_map(|v| get_inner_vec(v)).collect();
+        let vectors: Vec<Vec<f32>> = res
+            .result
+            .iter()
+            .filter_map(|p| get_inner_vec(p.vectors.as_ref()))
+            .collect();
         Ok(vectors)
     }
 
-    pub async fn get_embedding(&self, word: String) -> Option<Vec<f32>> {
+    async fn get_neighbors(
+        &self,
+        embedding: Vec<f32>,
+        how_many: u64,
+        exclude: &[String],
+    ) -> Result<Vec<Entry>> {
+        let conds: Vec<Condition> = exclude
+            .iter()
+            .map(|w| Condition::matches("word", w.clone()))
+            .collect();
+
+        let response = self
+            .query(
+                QueryPointsBuilder::new(&self.collection)
+                    .query(Query::new_nearest(embedding))
+                    .with_payload(true)
+                    .with_vectors(true)
+                    .filter(Filter::must_not(conds))
+                    .limit(how_many),
+            )
+            .await?;
+
+        Ok(get_neighbors_from_response(&response))
+    }
+
+    async fn get_embedding(&self, word: String) -> Option<Vec<f32>> {
         let response = self
             .scroll(
                 ScrollPointsBuilder::new(&self.collection)
@@ -135,19 +172,10 @@ impl Qdrnt {
             .await
             .ok()?;
 
-        if response.result.len() == 0 {
-            return None;
-        }
-        response.result[0]
-            .vectors
-            .as_ref()
-            .map(|inner| match inner.vectors_options {
-                Some(VectorsOptions::Vector(ref w)) => w.data.clone(),
-                _ => unreachable!("we're not using sparse vecs!"),
-            })
+        get_inner_vec(response.result.first()?.vectors.as_ref())
     }
 
-    pub async fn get_word(&self, embedding: Vec<f32>) -> Result<String> {
+    async fn get_word(&self, embedding: Vec<f32>) -> Result<String> {
         let response = self
             .query(
                 QueryPointsBuilder::new(&self.collection)
@@ -157,18 +185,52 @@ impl Qdrnt {
             )
             .await?;
 
-        let word = response.result[0]
-            .payload
-            .get("word")
-            .unwrap()
-            .as_str()
-            .unwrap()
-            .to_owned();
+        response
+            .result
+            .first()
+            .and_then(|point| point.payload.get("word")?.as_str())
+            .map(|word| word.to_owned())
+            .ok_or_else(|| KontekstoError::EmptyCollection(self.collection.clone()))
+    }
+
+    /// scroll through the whole collection, returning every word with its embedding
+    async fn get_all_entries(&self) -> Result<Vec<Entry>> {
+        let mut entries = vec![];
+        let mut offset = None;
+
+        loop {
+            let mut request = ScrollPointsBuilder::new(&self.collection)
+                .limit(1024)
+                .with_payload(true)
+                .with_vectors(true);
+            if let Some(id) = offset {
+                request = request.offset(id);
+            }
+            let response = self.scroll(request).await?;
+
+            for point in response.result.iter() {
+                let Some(word) = point.payload.get("word").and_then(|w| w.as_str()) else {
+                    continue;
+                };
+                // a word without a single dense embedding can't be searched
+                let embedding = get_inner_vec(point.vectors.as_ref())
+                    .ok_or_else(|| KontekstoError::MissingEmbedding(word.to_owned()))?;
+                entries.push(Entry {
+                    word: word.to_owned(),
+                    embedding,
+                });
+            }
+
+            match response.next_page_offset {
+                Some(id) => offset = Some(id),
+                None => break,
+            }
+        }
 
-        Ok(word)
+        Ok(entries)
     }
 
-    pub async fn count_points(&self) -> Option<u64> {
+    async fn count_points(&self) -> Option<u64> {
         let response = self
             .count(CountPointsBuilder::new(&self.collection))
             .await
@@ -176,20 +238,4 @@ impl Qdrnt {
 
         response.result.map(|res| res.count)
     }
-
-    pub async fn context_search(&self, positive_context: Vec<Vec<f32>>, n: u64) -> Result<Vec<Entry>>{
-
-        let mut context = RecommendInputBuilder::default();
-        for example in positive_context{
-            context = context.add_positive(example);
-        }
-
-        let response = self.query(
-            QueryPointsBuilder::new("{collection_name}")
-                .query(context.build())
-                .limit(n),
-        ).await?;
-
-        Ok(get_neighbors_from_response(&response))
-    }
 }
@@ -0,0 +1,173 @@
+use std::{
+    collections::HashSet,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::clients::cache::{end_last_line, read_lines};
+use crate::config::Lang;
+
+/// Words contexto refused to score in one language, one per line.
+///
+/// Our vocabularies come from a generic dump and contain plenty of words contexto
+/// doesn't know or doesn't count; learning them across games lets the solver stop
+/// wasting guesses on them. New words are appended as they are found.
+pub struct RejectList {
+    /// none for lists kept in memory only
+    path: Option<PathBuf>,
+    words: Mutex<HashSet<String>>,
+}
+
+impl RejectList {
+    /// location of the list for `lang` inside `dir`
+    pub fn file(dir: &str, lang: Lang) -> String {
+        format!("{}/{}.txt", dir, lang)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let words = match path.exists() {
+            true => {
+                end_last_line(&path)?;
+                read_words(&path)?
+            }
+            false => HashSet::new(),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            words: Mutex::new(words),
+        })
+    }
+
+    /// list which is never written to disk, e.g. the rejects recorded in a trace
+    pub fn in_memory(words: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            path: None,
+            words: Mutex::new(words.into_iter().collect()),
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.lock().unwrap().contains(word)
+    }
+
+    /// record `words`, returning how many weren't known yet
+    pub fn extend(&self, words: impl IntoIterator<Item = String>) -> std::io::Result<usize> {
+        let mut known = self.words.lock().unwrap();
+        let new: Vec<String> = words
+            .into_iter()
+            .filter(|w| !w.is_empty() && !known.contains(w))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if new.is_empty() {
+            return Ok(0);
+        }
+
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            for word in new.iter() {
+                writeln!(file, "{}", word)?;
+            }
+        }
+
+        known.extend(new.iter().cloned());
+        Ok(new.len())
+    }
+
+    pub fn insert(&self, word: &str) -> std::io::Result<bool> {
+        Ok(self.extend([word.to_owned()])? > 0)
+    }
+
+    /// every rejected word, sorted
+    pub fn words(&self) -> Vec<String> {
+        let mut words: Vec<String> = self.words.lock().unwrap().iter().cloned().collect();
+        words.sort();
+        words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// words of a list file, ignoring blank lines and lines which aren't valid utf-8
+pub fn read_words(path: impl AsRef<Path>) -> std::io::Result<HashSet<String>> {
+    Ok(read_lines(path.as_ref())?.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("rejects-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn new_words_are_appended_once() {
+        let dir = temp_dir();
+        let path = RejectList::file(dir.to_str().unwrap(), Lang::En);
+
+        let rejects = RejectList::open(&path).unwrap();
+        assert!(rejects.is_empty());
+        assert!(rejects.insert("the").unwrap());
+        assert!(!rejects.insert("the").unwrap(), "already known");
+        let added = rejects
+            .extend(["of", "the", "of", ""].map(str::to_owned))
+            .unwrap();
+        assert_eq!(added, 1);
+
+        let lines = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(lines.lines().count(), 2);
+        assert_eq!(RejectList::open(&path).unwrap().words(), ["of", "the"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn open_ends_a_truncated_last_line() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("en.txt");
+        std::fs::write(&path, "the\n\nof").unwrap();
+
+        let rejects = RejectList::open(&path).unwrap();
+        assert_eq!(rejects.words(), ["of", "the"]);
+        rejects.insert("a").unwrap();
+        assert_eq!(RejectList::open(&path).unwrap().words(), ["a", "of", "the"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn exported_words_import_into_another_list() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let exported = dir.join("export.txt");
+        std::fs::write(&exported, "the\nof\n").unwrap();
+
+        let rejects = RejectList::open(dir.join("en.txt")).unwrap();
+        rejects.insert("of").unwrap();
+        assert_eq!(rejects.extend(read_words(&exported).unwrap()).unwrap(), 1);
+        assert_eq!(rejects.words(), ["of", "the"]);
+
+        // lists kept in memory never touch the disk
+        let in_memory = RejectList::in_memory(read_words(&exported).unwrap());
+        assert_eq!(in_memory.extend(["a".to_owned()]).unwrap(), 1);
+        assert_eq!(in_memory.len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
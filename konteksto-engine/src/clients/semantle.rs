@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::clients::ApiClient;
+use crate::config::ClientConfig;
+use crate::errors::{KontekstoError, Result};
+use crate::oracle::ScoreOracle;
+
+/// json schema of a scored guess; semantle calls the similarity `sim`, its clones `score`
+#[derive(Deserialize)]
+struct ScorePayload {
+    #[serde(alias = "score")]
+    sim: f32,
+}
+
+/// A struct for making api calls to semantle-style games, which answer a guess with its
+/// similarity to the answer instead of a rank.
+///
+/// `url` is the address a guess is scored at, with a `{word}` placeholder, e.g.
+/// `https://semantle.com/model2/<secret>/{word}`; the word is appended if it has none.
+#[derive(Clone)]
+pub struct Semantle {
+    http: ApiClient,
+    url: String,
+    /// whether the game reports similarities in percent rather than as a cosine
+    percent: bool,
+}
+
+impl Semantle {
+    pub fn new(url: &str) -> Self {
+        let url = match url.contains("{word}") {
+            true => url.to_owned(),
+            false => format!("{}/{{word}}", url.trim_end_matches('/')),
+        };
+        Self {
+            http: ApiClient::default(),
+            url,
+            percent: false,
+        }
+    }
+
+    /// read every similarity as a percentage, as some clones report them
+    pub fn with_percent(mut self, percent: bool) -> Self {
+        self.percent = percent;
+        self
+    }
+
+    /// pace, retry and time out requests following `settings`
+    pub fn with_client_config(mut self, settings: ClientConfig) -> Result<Self> {
+        self.http = ApiClient::new(settings)?;
+        Ok(self)
+    }
+
+    /// cosine similarity of `word` to the answer, 1 being the answer
+    pub async fn similarity(&self, word: &str) -> Result<f32> {
+        let url = self.url.replace("{word}", word);
+        let unknown = || KontekstoError::UnknownWord(word.to_owned());
+        let payload: serde_json::Value = match self.http.get(&url, |_, _| unknown()).await {
+            Ok(payload) => payload,
+            // unknown words may get an empty or non-json body rather than an error status
+            Err(KontekstoError::ContextoError(e)) if e.is_decode() => return Err(unknown()),
+            Err(e) => return Err(e),
+        };
+        let payload: ScorePayload = serde_json::from_value(payload).map_err(|_| unknown())?;
+
+        Ok(self.scale(payload.sim))
+    }
+
+    /// similarity reported by the game as a cosine; the scale can't be told from a single
+    /// response since words far from the answer score under 1% in percent games
+    fn scale(&self, sim: f32) -> f32 {
+        match self.percent {
+            true => sim / 100.0,
+            false => sim,
+        }
+    }
+}
+
+#[async_trait]
+impl ScoreOracle for Semantle {
+    async fn similarity(&self, word: &str) -> Result<f32> {
+        Semantle::similarity(self, word).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cosine_scores_are_kept() {
+        let semantle = Semantle::new("http://localhost");
+        assert_eq!(semantle.scale(1.0), 1.0);
+        assert_eq!(semantle.scale(0.8), 0.8);
+        assert_eq!(semantle.scale(-0.2), -0.2);
+    }
+
+    #[test]
+    fn percent_scores_are_scaled_at_and_below_one() {
+        let semantle = Semantle::new("http://localhost").with_percent(true);
+        assert_eq!(semantle.scale(100.0), 1.0);
+        assert_eq!(semantle.scale(1.0), 0.01);
+        assert_eq!(semantle.scale(0.8), 0.008);
+        assert_eq!(semantle.scale(-0.5), -0.005);
+    }
+}
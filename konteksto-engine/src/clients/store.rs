@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::errors::Result;
+
+/// jsonl schema from python dump
+#[derive(Deserialize, Debug, Clone)]
+pub struct Entry {
+    pub word: String,
+    pub embedding: Vec<f32>,
+}
+impl Entry {
+    pub fn read_from_dump(file: &str) -> anyhow::Result<Vec<Self>> {
+        let entries = std::fs::read_to_string(file)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        Ok(entries)
+    }
+}
+
+/// Everything the solvers need from a collection of word embeddings.
+///
+/// Embeddings are compared with cosine similarity and returned unit-normed, which is
+/// how qdrant stores vectors in a cosine collection.
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    /// name of the collection, i.e. the language
+    fn collection(&self) -> &str;
+
+    async fn get_random_vecs(&self, how_many: u64) -> Result<Vec<Vec<f32>>>;
+
+    /// nearest neighbors of `embedding`, skipping the words in `exclude`
+    async fn get_neighbors(
+        &self,
+        embedding: Vec<f32>,
+        how_many: u64,
+        exclude: &[String],
+    ) -> Result<Vec<Entry>>;
+
+    async fn get_embedding(&self, word: String) -> Option<Vec<f32>>;
+
+    async fn get_word(&self, embedding: Vec<f32>) -> Result<String>;
+
+    async fn count_points(&self) -> Option<u64>;
+
+    /// every word in the collection with its embedding
+    async fn get_all_entries(&self) -> Result<Vec<Entry>>;
+}
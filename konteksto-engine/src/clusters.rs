@@ -0,0 +1,143 @@
+use ndarray::{Array2, Axis};
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::clients::Entry;
+use crate::errors::Result;
+use crate::oracle::{normalize, unit_rows};
+
+/// k-means iterations used when clusters are built on the fly
+pub const DEFAULT_ITERS: usize = 20;
+
+/// k-means clusters of a collection, stored as a json sidecar next to the embeddings
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Clusters {
+    /// unit-norm cluster centers
+    pub centroids: Vec<Vec<f32>>,
+    /// word nearest to each centroid
+    pub representatives: Vec<String>,
+}
+
+impl Clusters {
+    /// location of the sidecar file for a collection and number of clusters
+    pub fn sidecar(collection: &str, k: usize) -> String {
+        format!("./data/clusters/{}-{}.json", collection, k)
+    }
+
+    pub fn load(file: &str) -> std::io::Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(file)?)?)
+    }
+
+    pub fn save(&self, file: &str) -> std::io::Result<()> {
+        if let Some(parent) = std::path::Path::new(file).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(file, serde_json::to_string(self)?)
+    }
+
+    /// spherical k-means: points and centroids live on the unit sphere and are
+    /// compared with cosine similarity, like the qdrant collection. The initial
+    /// centroids are drawn from `rng`, so a seeded one always gives the same clusters
+    pub fn fit(entries: &[Entry], k: usize, iters: usize, rng: &mut impl Rng) -> Result<Self> {
+        let n = entries.len();
+        let k = k.clamp(1, n.max(1));
+        let points: Array2<f32> = unit_rows(entries)?;
+
+        // random distinct points as initial centroids
+        let init: Vec<usize> = (0..n)
+            .collect::<Vec<_>>()
+            .choose_multiple(rng, k)
+            .copied()
+            .collect();
+        let mut centroids = points.select(Axis(0), &init);
+
+        let mut assignment = vec![0; n];
+        for _ in 0..iters {
+            let sims = points.dot(&centroids.t());
+            for (i, row) in sims.rows().into_iter().enumerate() {
+                assignment[i] = argmax(row.iter().copied());
+            }
+
+            for (c, mut centroid) in centroids.rows_mut().into_iter().enumerate() {
+                let members: Vec<usize> = (0..n).filter(|&i| assignment[i] == c).collect();
+                // empty clusters keep their previous center
+                if let Some(mean) = points.select(Axis(0), &members).mean_axis(Axis(0)) {
+                    centroid.assign(&normalize(mean));
+                }
+            }
+        }
+
+        let sims = points.dot(&centroids.t());
+        let representatives = (0..k)
+            .map(|c| {
+                let best = argmax(sims.column(c).iter().copied());
+                entries[best].word.clone()
+            })
+            .collect();
+
+        Ok(Self {
+            centroids: centroids.rows().into_iter().map(|r| r.to_vec()).collect(),
+            representatives,
+        })
+    }
+}
+
+fn argmax(values: impl Iterator<Item = f32>) -> usize {
+    values
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(0, |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    /// `n` words around each of the axes of the plane
+    fn two_groups(n: usize) -> Vec<Entry> {
+        (0..n)
+            .flat_map(|i| {
+                let eps = 0.01 * i as f32;
+                [
+                    Entry {
+                        word: format!("x{}", i),
+                        embedding: vec![1.0, eps],
+                    },
+                    Entry {
+                        word: format!("y{}", i),
+                        embedding: vec![eps, 1.0],
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fit_separates_groups_the_same_way_for_a_seed() {
+        let entries = two_groups(10);
+        let fit = |seed| Clusters::fit(&entries, 2, 10, &mut StdRng::seed_from_u64(seed)).unwrap();
+
+        let clusters = fit(3);
+        let mut prefixes: Vec<char> = clusters
+            .representatives
+            .iter()
+            .map(|w| w.chars().next().unwrap())
+            .collect();
+        prefixes.sort();
+        assert_eq!(prefixes, ['x', 'y'], "one representative per group");
+        for centroid in clusters.centroids.iter() {
+            let norm = centroid.iter().map(|x| x * x).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-5);
+        }
+
+        assert_eq!(fit(3).representatives, clusters.representatives);
+    }
+
+    #[test]
+    fn fit_asks_for_at_most_one_cluster_per_word() {
+        let entries = two_groups(1);
+        let clusters = Clusters::fit(&entries, 8, 5, &mut StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(clusters.centroids.len(), 2);
+    }
+}
@@ -1,12 +1,16 @@
 use std::str::FromStr;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use serde::{self, Deserialize, Serialize};
 
 const QDRANT_SERVICE_GRPC_PORT: &str = "QDRANT__SERVICE__GRPC_PORT";
 const QDRANT_SERVICE_GRPC_HOST: &str = "QDRANT__SERVICE__GRPC_HOST";
+const CONTEXTO_API_URL: &str = "CONTEXTO_API_URL";
 
-#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+/// the real contexto api, whose responses and refusals are kept without a suffix
+pub const DEFAULT_CONTEXTO_URL: &str = "https://api.contexto.me/machado";
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum Lang {
     #[default]
@@ -15,12 +19,12 @@ pub enum Lang {
     Pt,
     Es,
 }
-impl ToString for Lang {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for Lang {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Lang::En => "en".to_string(),
-            Lang::Pt => "pt-br".to_string(),
-            Lang::Es => "es".to_string(),
+            Lang::En => write!(f, "en"),
+            Lang::Pt => write!(f, "pt-br"),
+            Lang::Es => write!(f, "es"),
         }
     }
 }
@@ -38,6 +42,49 @@ impl FromStr for Lang {
     }
 }
 
+/// search algorithm used to solve a game
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    /// greedy hill climbing with momentum
+    #[default]
+    HillClimb,
+    /// estimate the target embedding from every observed rank
+    Triangulation,
+    /// narrow down the set of possible answers with maximally informative guesses
+    Elimination,
+    /// hill climbing which accepts worse moves with a decaying probability
+    Annealing,
+    /// particle swarm moving several query points at once
+    Swarm,
+    /// expand the best few words found so far at every step
+    Beam,
+}
+
+/// how seeds are picked at the start of a game
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Opening {
+    /// random words of the collection
+    #[default]
+    Random,
+    /// probe one word per k-means cluster and start from the most promising centroids
+    Clusters,
+}
+
+/// how the temperature decays in simulated annealing
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Schedule {
+    /// `t0 * cooling^k`
+    #[default]
+    Exponential,
+    /// `t0 * (1 - k / max_iters)`
+    Linear,
+    /// `t0 / ln(k + 2)`
+    Logarithmic,
+}
+
 #[derive(Parser, Serialize, Deserialize, Debug, Clone)]
 pub struct Args {
     #[clap(long, default_value_t = 42)]
@@ -55,13 +102,172 @@ pub struct Args {
     #[clap(long, env = QDRANT_SERVICE_GRPC_HOST, default_value="localhost")]
     pub grpc_host: String,
 
+    /// jsonl file caching contexto responses across runs
+    #[clap(long, default_value = "./data/cache/contexto.jsonl")]
+    pub cache_file: String,
+
+    /// always query the contexto api, bypassing the response cache
+    #[serde(default)]
+    #[clap(long)]
+    pub no_cache: bool,
+
+    /// directory of the per-language lists of words contexto refused to score
+    #[clap(long, default_value = "./data/rejects")]
+    pub rejects_dir: String,
+
+    /// json file holding a tuned optimizer config, which overrides the optimizer flags;
+    /// `{lang}` is replaced by the language, e.g. `./data/optimizer/{lang}.json`
+    #[clap(long)]
+    pub optimizer_file: Option<String>,
+
+    /// seed of the random choices made while solving, e.g. annealing moves or swarm
+    /// particles; drawn at random when missing and recorded in traces
+    #[serde(default)]
+    #[clap(long)]
+    pub seed: Option<u64>,
+
+    #[serde(flatten)]
+    #[clap(flatten)]
+    pub client_config: ClientConfig,
+
     #[serde(flatten)]
     #[clap(flatten)]
     pub optimizer_config: OptimizerConfig,
 }
 
-#[derive(Parser, Default, Serialize, Deserialize, Debug, Copy, Clone)]
+impl Args {
+    /// replace the optimizer config with the one in `optimizer_file`, if any; `{lang}`
+    /// in the file name is replaced by the language
+    pub fn load_optimizer_file(mut self) -> std::io::Result<Self> {
+        if let Some(file) = &self.optimizer_file {
+            let file = file.replace("{lang}", &self.lang.to_string());
+            self.optimizer_config = OptimizerConfig::from_file(&file)?;
+        }
+        Ok(self)
+    }
+
+    /// response cache of the configured contexto api, see `ClientConfig::partition`
+    pub fn cache_path(&self) -> String {
+        let Some(partition) = self.client_config.partition() else {
+            return self.cache_file.clone();
+        };
+        let path = std::path::Path::new(&self.cache_file);
+        let stem = path.file_stem().map_or(String::new(), |s| s.to_string_lossy().into());
+        let name = match path.extension() {
+            Some(ext) => format!("{}-{}.{}", stem, partition, ext.to_string_lossy()),
+            None => format!("{}-{}", stem, partition),
+        };
+        path.with_file_name(name).to_string_lossy().into()
+    }
+
+    /// directory of the reject lists of the configured contexto api, see
+    /// `ClientConfig::partition`
+    pub fn rejects_path(&self) -> String {
+        match self.client_config.partition() {
+            Some(partition) => format!("{}/{}", self.rejects_dir, partition),
+            None => self.rejects_dir.clone(),
+        }
+    }
+}
+
+/// how requests to the contexto api are paced and retried
+#[derive(Parser, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClientConfig {
+    /// base url of the contexto api, e.g. to play against a local `mock-contexto` server
+    #[serde(default = "default_contexto_url")]
+    #[clap(long, env = CONTEXTO_API_URL, default_value = DEFAULT_CONTEXTO_URL)]
+    pub contexto_url: String,
+
+    /// max requests per second sent to contexto, 0 to disable the limit
+    #[serde(default = "default_rate_limit")]
+    #[clap(long, default_value_t = DEFAULT_RATE_LIMIT)]
+    pub rate_limit: f64,
+
+    /// attempts per request; 429s, 5xx and timeouts are retried with exponential backoff
+    #[serde(default = "default_max_attempts")]
+    #[clap(long, default_value_t = DEFAULT_MAX_ATTEMPTS)]
+    pub max_attempts: u32,
+
+    /// initial delay between attempts in ms, doubled after every failure
+    #[serde(default = "default_backoff_ms")]
+    #[clap(long, default_value_t = DEFAULT_BACKOFF_MS)]
+    pub backoff_ms: u64,
+
+    /// timeout of a single request in ms
+    #[serde(default = "default_timeout_ms")]
+    #[clap(long, default_value_t = DEFAULT_TIMEOUT_MS)]
+    pub timeout_ms: u64,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            contexto_url: default_contexto_url(),
+            rate_limit: default_rate_limit(),
+            max_attempts: default_max_attempts(),
+            backoff_ms: default_backoff_ms(),
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Name telling apart what's recorded against another api than contexto.me, e.g. a
+    /// `mock-contexto` server, so its ranks and refusals never leak into real games.
+    /// None for contexto.me itself
+    pub fn partition(&self) -> Option<String> {
+        let url = self.contexto_url.trim_end_matches('/');
+        if url == DEFAULT_CONTEXTO_URL {
+            return None;
+        }
+
+        Some(file_name(url))
+    }
+}
+
+/// `url` without its scheme, fit to be part of a file name
+pub(crate) fn file_name(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+    let host = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let name: String = host
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    name.trim_matches('-').to_owned()
+}
+
+fn default_contexto_url() -> String {
+    DEFAULT_CONTEXTO_URL.to_string()
+}
+
+// defaults of the flags, also filled in for configs saved before the field existed
+const DEFAULT_RATE_LIMIT: f64 = 5.0;
+fn default_rate_limit() -> f64 {
+    DEFAULT_RATE_LIMIT
+}
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+fn default_max_attempts() -> u32 {
+    DEFAULT_MAX_ATTEMPTS
+}
+
+const DEFAULT_BACKOFF_MS: u64 = 250;
+fn default_backoff_ms() -> u64 {
+    DEFAULT_BACKOFF_MS
+}
+
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+fn default_timeout_ms() -> u64 {
+    DEFAULT_TIMEOUT_MS
+}
+
+#[derive(Parser, Default, Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub struct OptimizerConfig {
+    /// search algorithm used to solve the game
+    #[serde(default)]
+    #[clap(long, value_enum, default_value_t = Strategy::HillClimb)]
+    pub strategy: Strategy,
+
     /// number of times to randomly initialize search algorithm
     #[clap(long, default_value_t = 1)]
     pub max_retries: usize,
@@ -77,4 +283,165 @@ pub struct OptimizerConfig {
     /// value under which "free mobility" is possible
     #[clap(long, default_value_t = 200)]
     pub margin: u32,
+
+    /// number of nearest neighbors scored at each step
+    #[serde(default = "default_neighbors")]
+    #[clap(long, default_value_t = DEFAULT_NEIGHBORS)]
+    pub neighbors: u64,
+
+    /// cosine slack allowed when checking candidate answers against observed ranks
+    #[serde(default = "default_tolerance")]
+    #[clap(long, default_value_t = DEFAULT_TOLERANCE)]
+    pub tolerance: f32,
+
+    /// initial temperature in simulated annealing
+    #[serde(default = "default_temperature")]
+    #[clap(long, default_value_t = DEFAULT_TEMPERATURE)]
+    pub temperature: f32,
+
+    /// temperature decay schedule in simulated annealing
+    #[serde(default)]
+    #[clap(long, value_enum, default_value_t = Schedule::Exponential)]
+    pub schedule: Schedule,
+
+    /// decay rate of the exponential temperature schedule
+    #[serde(default = "default_cooling")]
+    #[clap(long, default_value_t = DEFAULT_COOLING)]
+    pub cooling: f32,
+
+    /// number of particles in the swarm strategy
+    #[serde(default = "default_population")]
+    #[clap(long, default_value_t = DEFAULT_POPULATION)]
+    pub population: usize,
+
+    /// number of words kept on the frontier in beam search; each is expanded with `neighbors` words
+    #[serde(default = "default_beam_width")]
+    #[clap(long, default_value_t = DEFAULT_BEAM_WIDTH)]
+    pub beam_width: usize,
+
+    /// how seeds are picked at the start of a game
+    #[serde(default)]
+    #[clap(long, value_enum, default_value_t = Opening::Random)]
+    pub opening: Opening,
+
+    /// number of k-means clusters probed by the cluster opening
+    #[serde(default = "default_clusters")]
+    #[clap(long, default_value_t = DEFAULT_CLUSTERS)]
+    pub clusters: usize,
+}
+
+const DEFAULT_NEIGHBORS: u64 = 3;
+fn default_neighbors() -> u64 {
+    DEFAULT_NEIGHBORS
+}
+
+const DEFAULT_TOLERANCE: f32 = 0.05;
+fn default_tolerance() -> f32 {
+    DEFAULT_TOLERANCE
+}
+
+const DEFAULT_TEMPERATURE: f32 = 1.0;
+fn default_temperature() -> f32 {
+    DEFAULT_TEMPERATURE
+}
+
+const DEFAULT_COOLING: f32 = 0.95;
+fn default_cooling() -> f32 {
+    DEFAULT_COOLING
+}
+
+const DEFAULT_POPULATION: usize = 5;
+fn default_population() -> usize {
+    DEFAULT_POPULATION
+}
+
+const DEFAULT_BEAM_WIDTH: usize = 3;
+fn default_beam_width() -> usize {
+    DEFAULT_BEAM_WIDTH
+}
+
+const DEFAULT_CLUSTERS: usize = 32;
+fn default_clusters() -> usize {
+    DEFAULT_CLUSTERS
+}
+
+impl OptimizerConfig {
+    /// where `tune` writes the best config found for `lang`
+    pub fn tuned_file(lang: Lang) -> String {
+        format!("./data/optimizer/{}.json", lang)
+    }
+
+    pub fn from_file(file: &str) -> std::io::Result<Self> {
+        let config = serde_json::from_str(&std::fs::read_to_string(file)?)?;
+        Ok(config)
+    }
+
+    pub fn to_file(&self, file: &str) -> std::io::Result<()> {
+        if let Some(parent) = std::path::Path::new(file).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(file, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::{CachedResponse, RejectList, ResponseCache};
+
+    fn args(url: &str, dir: &std::path::Path) -> Args {
+        Args::parse_from([
+            "solve",
+            "--contexto-url",
+            url,
+            "--cache-file",
+            &dir.join("contexto.jsonl").to_string_lossy(),
+            "--rejects-dir",
+            &dir.join("rejects").to_string_lossy(),
+        ])
+    }
+
+    #[test]
+    fn other_apis_get_their_own_cache_and_rejects() {
+        let dir = std::env::temp_dir().join(format!("konteksto-{}", uuid::Uuid::new_v4()));
+        let real = args(DEFAULT_CONTEXTO_URL, &dir);
+        let mock = args("http://localhost:5049/", &dir);
+
+        assert_eq!(real.cache_path(), real.cache_file);
+        assert_eq!(real.rejects_path(), real.rejects_dir);
+        assert!(mock.cache_path().ends_with("contexto-localhost-5049.jsonl"));
+        assert!(mock.rejects_path().ends_with("rejects/localhost-5049"));
+
+        // what the mock answers never shows up for the real api
+        let response = CachedResponse {
+            lang: Lang::En,
+            game_id: 1,
+            word: "cat".to_owned(),
+            distance: 0,
+            lemma: "cat".to_owned(),
+        };
+        ResponseCache::open(mock.cache_path()).unwrap().insert(response).unwrap();
+        let mock_rejects = RejectList::open(RejectList::file(&mock.rejects_path(), Lang::En));
+        mock_rejects.unwrap().insert("the").unwrap();
+
+        let cache = ResponseCache::open(real.cache_path()).unwrap();
+        assert_eq!(cache.get(Lang::En, 1, "cat"), None);
+        let rejects = RejectList::open(RejectList::file(&real.rejects_path(), Lang::En)).unwrap();
+        assert!(!rejects.contains("the"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn saved_configs_missing_a_field_get_the_flag_default() {
+        let flags = Args::parse_from(["solve"]);
+
+        let saved = r#"{"max_retries": 1, "max_iters": 100, "beta": 0.5, "margin": 200}"#;
+        let optimizer: OptimizerConfig = serde_json::from_str(saved).unwrap();
+        assert_eq!(optimizer, flags.optimizer_config);
+
+        let client: ClientConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(client, flags.client_config);
+        assert_eq!(client, ClientConfig::default());
+    }
 }
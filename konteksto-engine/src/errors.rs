@@ -1,19 +1,82 @@
 use ndarray::ShapeError;
+#[cfg(feature = "qdrant")]
 use qdrant_client::QdrantError;
 
 pub type Result<T> = std::result::Result<T, KontekstoError>;
 
 #[derive(thiserror::Error, Debug)]
 pub enum KontekstoError {
-    #[error(transparent)]
-    QdrantError(#[from] anyhow::Error),
+    /// contexto doesn't know the word
+    #[error("'{0}' is not in contexto's dictionary")]
+    UnknownWord(String),
+
+    /// contexto knows the word but doesn't count it, e.g. articles or pronouns
+    #[error("'{0}' is too common to be scored")]
+    CommonWord(String),
+
+    #[error("game {0} not found")]
+    GameNotFound(u32),
+
+    #[error("rate limited by contexto")]
+    RateLimited,
+
+    /// contexto timed out, refused the connection or failed on its end
+    #[error("contexto is unavailable: {0}")]
+    Unavailable(String),
 
+    /// any other failed call to contexto, e.g. an unexpected response
     #[error(transparent)]
     ContextoError(#[from] reqwest::Error),
 
+    // boxed, qdrant errors are large
+    #[cfg(feature = "qdrant")]
+    #[error("vector store: {0}")]
+    StoreError(Box<QdrantError>),
+
+    #[error("collection '{0}' is empty")]
+    EmptyCollection(String),
+
+    /// every word of the collection was guessed, skipped or refused already
+    #[error("no unseen words left in '{0}'")]
+    Exhausted(String),
+
+    #[error("no embedding for '{0}'")]
+    MissingEmbedding(String),
+
+    /// `line` counts the entries of the collection from 1, i.e. the line of a dump
+    #[error("line {line}: '{word}' has {got} dimensions, expected {expected}")]
+    DimensionMismatch {
+        line: usize,
+        word: String,
+        got: usize,
+        expected: usize,
+    },
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
     #[error("linalg")]
     LinalgError(#[from] ShapeError),
 
     #[error(transparent)]
-    Other(#[from] QdrantError)
+    Other(#[from] anyhow::Error),
+}
+
+#[cfg(feature = "qdrant")]
+impl From<QdrantError> for KontekstoError {
+    fn from(e: QdrantError) -> Self {
+        Self::StoreError(Box::new(e))
+    }
+}
+
+impl KontekstoError {
+    /// whether the same call may succeed later, e.g. after a timeout or rate limiting
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::RateLimited | Self::Unavailable(_))
+    }
+
+    /// whether contexto refused to score the word itself, as opposed to failing
+    pub fn is_rejected_word(&self) -> bool {
+        matches!(self, Self::UnknownWord(_) | Self::CommonWord(_))
+    }
 }
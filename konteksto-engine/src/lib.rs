@@ -1,16 +1,94 @@
+pub mod bench;
+pub mod checkpoint;
 pub mod clients;
+pub mod clusters;
 pub mod config;
 pub mod errors;
+pub mod oracle;
 pub mod solver;
 pub mod title;
+pub mod trace;
 
-use std::path::Path;
+use std::sync::Arc;
 
+use clients::{Contexto, RejectList, ResponseCache, Semantle, VectorStore};
+
+#[cfg(feature = "qdrant")]
 pub use clients::Qdrnt;
+pub use clients::MemoryStore;
 pub use config::Args;
+pub use oracle::{LocalOracle, RankOracle, ScoreAdapter};
 pub use solver::Solver;
 
 pub async fn setup(config: Args) -> crate::errors::Result<Solver> {
+    let store = setup_store(&config).await?;
+    let rejects = setup_rejects(&config)?;
+    let contexto = setup_contexto(&config)?.with_rejects(rejects.clone());
+    Ok(Solver::with_oracle(config, store, contexto).with_rejects(rejects))
+}
+
+/// words the configured contexto api refused in `config.lang` so far
+pub fn setup_rejects(config: &Args) -> std::io::Result<Arc<RejectList>> {
+    let file = RejectList::file(&config.rejects_path(), config.lang);
+    Ok(Arc::new(RejectList::open(file)?))
+}
+
+/// contexto client for the configured game, backed by the response cache unless disabled
+pub fn setup_contexto(config: &Args) -> crate::errors::Result<Contexto> {
+    let contexto = Contexto::new(config.lang, config.game_id)
+        .with_client_config(config.client_config.clone())?;
+    if config.no_cache {
+        return Ok(contexto);
+    }
+
+    let cache = ResponseCache::open(config.cache_path())?;
+    Ok(contexto.with_cache(Arc::new(cache)))
+}
+
+/// solver scoring guesses offline against `target` rather than the contexto api
+pub async fn setup_local(config: Args, target: &str) -> crate::errors::Result<Solver<LocalOracle>> {
+    let store = setup_store(&config).await?;
+    let oracle = LocalOracle::new(store.as_ref(), target).await?;
+    Ok(Solver::with_oracle(config, store, oracle))
+}
+
+/// solver for a semantle-style game scoring guesses at `url`, see `Semantle`. `percent`
+/// tells whether the game reports similarities in percent
+pub async fn setup_semantle(
+    config: Args,
+    url: &str,
+    percent: bool,
+) -> crate::errors::Result<Solver<ScoreAdapter<Semantle>>> {
+    let store = setup_store(&config).await?;
+    let semantle = Semantle::new(url)
+        .with_percent(percent)
+        .with_client_config(config.client_config.clone())?;
+    Ok(Solver::with_oracle(config, store, ScoreAdapter::new(semantle)))
+}
+
+/// vector store for `config.lang`, as picked by the `qdrant` feature
+pub async fn setup_store(config: &Args) -> crate::errors::Result<Arc<dyn VectorStore>> {
+    #[cfg(feature = "qdrant")]
+    let store = setup_qdrant(config).await?;
+
+    #[cfg(not(feature = "qdrant"))]
+    let store = setup_memory(config)?;
+
+    Ok(Arc::new(store))
+}
+
+/// load the embeddings for `config.lang` in memory
+pub fn setup_memory(config: &Args) -> crate::errors::Result<MemoryStore> {
+    let collection = config.lang.to_string();
+    let file = format!("./data/embeds/{}-embeds.txt", &collection);
+
+    println!("loading embeddings for {}", &collection);
+    MemoryStore::from_dump(&file, &collection)
+}
+
+/// connect to qdrant, building the collection for `config.lang` if needed
+#[cfg(feature = "qdrant")]
+pub async fn setup_qdrant(config: &Args) -> crate::errors::Result<Qdrnt> {
     let lang = &config.lang;
     let collection = lang.to_string();
 
@@ -20,7 +98,7 @@ pub async fn setup(config: Args) -> crate::errors::Result<Solver> {
     // build qdrant collection for lang
     let file = format!("./data/embeds/{}-embeds.txt", &collection);
 
-    if Path::new(&file).exists() {
+    if std::path::Path::new(&file).exists() {
         if !client.collection_exists(&collection).await? {
             println!("building qdrant index for {}", &collection);
             client
@@ -31,5 +109,5 @@ pub async fn setup(config: Args) -> crate::errors::Result<Solver> {
         println!("WARN: embeddings for collection '{}' not found", &collection);
     }
 
-    Ok(Solver::new(config, client))
+    Ok(client)
 }
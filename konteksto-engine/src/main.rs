@@ -2,27 +2,221 @@ use anyhow::Result;
 use clap::Parser;
 use konteksto_engine::title::render_title;
 use konteksto_engine::{
-    config::Args,
-    solver::solve_with_restarts,
+    RankOracle, Solver,
+    checkpoint::Checkpoint,
+    config::{Args, Lang},
+    oracle::RecordingOracle,
+    solver::{AnySolver, LinearSolver, SolveEvent, print_event, solve_with_restarts_observed},
+    trace::{TraceConfig, TraceWriter},
 };
-use konteksto_engine::setup;
+use konteksto_engine::{setup, setup_local, setup_semantle};
+
+#[derive(Parser, Debug)]
+struct Cli {
+    #[clap(flatten)]
+    config: Args,
+
+    /// solve offline against this target word instead of querying contexto
+    #[clap(long)]
+    target: Option<String>,
+
+    /// solve a semantle-style game scoring guesses at this url, where `{word}` is
+    /// replaced by the guess, instead of contexto
+    #[clap(long)]
+    semantle_url: Option<String>,
+
+    /// the semantle-style game reports similarities in percent rather than as a cosine
+    #[clap(long, requires = "semantle_url")]
+    semantle_percent: bool,
+
+    /// record every step of the solve to this jsonl file, see the `replay` binary
+    #[clap(long)]
+    trace: Option<String>,
+
+    /// file the solver progress is saved to after every step
+    /// [default: ./data/checkpoints/{lang}-{game_id}.json]
+    #[clap(long)]
+    checkpoint: Option<String>,
+
+    /// pick up an interrupted solve from its checkpoint
+    #[clap(long)]
+    resume: bool,
+}
+
+/// everything done around the solve besides printing progress
+struct Session {
+    tracer: Option<TraceWriter>,
+    checkpoint_file: String,
+    resume: bool,
+    lang: Lang,
+    game_id: u32,
+    target: Option<String>,
+    /// only set when playing semantle rather than a known target
+    semantle_url: Option<String>,
+}
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
-    let config = Args::parse();
+    let Cli {
+        config,
+        target,
+        semantle_url,
+        semantle_percent,
+        trace,
+        checkpoint,
+        resume,
+    } = Cli::parse();
+    let config = config.load_optimizer_file()?;
     render_title();
     dbg!("{:?}", &config);
 
-    // try solving with max retries
-    let mut solver = setup(config.clone()).await?;
-    let max_retries = config.optimizer_config.max_retries;
+    let tracer = trace.map(|file| TraceWriter::create(&file)).transpose()?;
+
+    // a known target is solved locally, whatever the semantle url
+    let semantle = semantle_url.as_ref().filter(|_| target.is_none());
+    let checkpoint_file = checkpoint.unwrap_or_else(|| {
+        Checkpoint::default_file(
+            config.lang,
+            config.game_id,
+            target.as_deref(),
+            semantle.map(String::as_str),
+        )
+    });
+    let session = Session {
+        tracer,
+        checkpoint_file,
+        resume,
+        lang: config.lang,
+        game_id: config.game_id,
+        target: target.clone(),
+        semantle_url: semantle.cloned(),
+    };
+    match (target, semantle_url) {
+        (Some(target), _) => run(setup_local(config, &target).await?, session).await,
+        (None, Some(url)) => {
+            let solver = setup_semantle(config, &url, semantle_percent).await?;
+            run(solver, session).await
+        }
+        (None, None) => run(setup(config).await?, session).await,
+    }
+}
+
+/// try solving with max retries
+async fn run<O: RankOracle + Send + Sync>(solver: Solver<O>, mut session: Session) -> Result<()> {
+    match session.tracer.take() {
+        Some(mut tracer) => {
+            tracer.config(TraceConfig {
+                lang: session.lang,
+                game_id: session.game_id,
+                target: session.target.clone(),
+                optimizer: *solver.settings(),
+                seed: Some(solver.seed()),
+                rejects: solver.rejects(),
+                feedback: solver.oracle.feedback(),
+            })?;
+
+            // every answer of the oracle goes to the trace
+            let recording = tracer.recording();
+            let solver = solver.map_oracle(|o| RecordingOracle::new(o, recording));
+            solve(solver, session, Some(tracer)).await
+        }
+        None => solve(solver, session, None).await,
+    }
+}
+
+async fn solve<O: RankOracle + Send + Sync>(
+    mut solver: Solver<O>,
+    session: Session,
+    mut tracer: Option<TraceWriter>,
+) -> Result<()> {
+    let Session {
+        checkpoint_file,
+        resume,
+        lang,
+        game_id,
+        target,
+        semantle_url,
+        ..
+    } = session;
 
+    // a resumed solve continues from the best word found so far
     let mut seeds = vec![];
-    for _ in 0..max_retries {
-        seeds.push(solver.generate_seed(1).await?);
+    let mut checkpoint = match resume {
+        true => {
+            let checkpoint = Checkpoint::load(&checkpoint_file)?;
+            anyhow::ensure!(
+                checkpoint.is_for(lang, game_id, target.as_deref(), semantle_url.as_deref()),
+                "checkpoint {} was taken for another game",
+                checkpoint_file
+            );
+            solver.restore(checkpoint.state.clone());
+
+            let best = solver.current_best();
+            println!("resuming restart {} from ({}, {})", checkpoint.restart, best.0, best.1);
+            let seed = match solver.store.get_embedding(best.0).await {
+                Some(embedding) => embedding,
+                None => solver.generate_seed(1).await?,
+            };
+            seeds.push(seed);
+            checkpoint
+        }
+        false => Checkpoint {
+            lang,
+            game_id,
+            target,
+            semantle_url,
+            restart: 0,
+            state: solver.snapshot(),
+        },
+    };
+    let first_restart = checkpoint.restart;
+    let remaining = solver
+        .settings()
+        .max_retries
+        .saturating_sub(first_restart + seeds.len());
+
+    let mut solver = AnySolver::new(solver);
+    seeds.extend(solver.generate_seeds(remaining).await?);
+    if let Some(tracer) = tracer.as_mut() {
+        tracer.opening(&seeds)?;
     }
 
-    let best = solve_with_restarts(&mut solver, seeds).await;
+    let mut failed = false;
+    let best = solve_with_restarts_observed(&mut solver, seeds, |event| {
+        match &event {
+            SolveEvent::Restart(i) => checkpoint.restart = first_restart + i,
+            SolveEvent::Checkpoint(state) => {
+                checkpoint.state = state.clone();
+                if let Err(e) = checkpoint.save(&checkpoint_file) {
+                    println!("WARN: failed to save checkpoint: {}", e);
+                }
+            }
+            SolveEvent::Failed(_) => failed = true,
+            SolveEvent::Guess { .. } => {}
+        }
+        if let Some(Err(e)) = tracer.as_mut().map(|t| t.observe(event.clone())) {
+            println!("WARN: failed to write trace: {}", e);
+        }
+        print_event(event);
+    })
+    .await;
+
+    if let Some(tracer) = tracer.as_mut() {
+        tracer.finish(best.clone())?;
+    }
+    if !solver.failed().is_empty() {
+        println!("WARN: could not score {}", solver.failed().join(", "));
+    }
+    // nothing left to resume unless a restart was cut short, e.g. by an outage
+    match best.1 == 0 || !failed {
+        true => {
+            let _ = std::fs::remove_file(&checkpoint_file);
+        }
+        false => println!(
+            "solve was interrupted, pick it up with --resume from {}",
+            checkpoint_file
+        ),
+    }
     dbg!("{:?}", best);
 
     Ok(())
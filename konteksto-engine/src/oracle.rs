@@ -0,0 +1,461 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use ndarray::{Array1, Array2, ArrayView1};
+use serde::{Deserialize, Serialize};
+
+use crate::clients::{Contexto, Entry, VectorStore};
+use crate::errors::{KontekstoError, Result};
+use crate::solver::Attempt;
+
+/// Anything able to score a guess with a contexto-style rank (0 being the answer)
+#[async_trait]
+pub trait RankOracle: Sync {
+    async fn rank(&self, word: &str) -> Result<u32>;
+
+    /// rank of `word` along with the lemma it was scored as. oracles which don't
+    /// normalize guesses treat every word as its own lemma
+    async fn rank_lemma(&self, word: &str) -> Result<(u32, String)> {
+        Ok((self.rank(word).await?, word.to_owned()))
+    }
+
+    /// lemma of `word` if it is known without scoring it
+    fn known_lemma(&self, _word: &str) -> Option<String> {
+        None
+    }
+
+    /// what the distances handed out are derived from
+    fn feedback(&self) -> Feedback {
+        Feedback::Rank
+    }
+}
+
+/// kind of feedback a game gives on guesses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Feedback {
+    /// position of the guess among every word of the game, as in contexto
+    #[default]
+    Rank,
+    /// similarity of the guess to the answer, as in semantle
+    Score,
+}
+
+/// Anything able to score a guess with its similarity to the answer (1 being the answer)
+#[async_trait]
+pub trait ScoreOracle: Sync {
+    async fn similarity(&self, word: &str) -> Result<f32>;
+}
+
+/// distance units per unit of similarity, see `ScoreAdapter`
+pub const SCORE_RESOLUTION: f32 = 10_000.0;
+
+/// distance standing for `similarity`, 0 being the answer
+pub fn score_to_distance(similarity: f32) -> u32 {
+    ((1.0 - similarity.clamp(-1.0, 1.0)) * SCORE_RESOLUTION).round() as u32
+}
+
+/// similarity a distance was derived from
+pub fn distance_to_score(distance: u32) -> f32 {
+    1.0 - distance as f32 / SCORE_RESOLUTION
+}
+
+/// Lets the solvers play score based games by turning similarities into the distance
+/// `(1 - similarity) * SCORE_RESOLUTION`, which keeps guesses in the same order and
+/// the answer at 0 like a rank would.
+pub struct ScoreAdapter<S> {
+    pub inner: S,
+}
+
+impl<S> ScoreAdapter<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl<S: ScoreOracle + Send> RankOracle for ScoreAdapter<S> {
+    async fn rank(&self, word: &str) -> Result<u32> {
+        Ok(score_to_distance(self.inner.similarity(word).await?))
+    }
+
+    fn feedback(&self) -> Feedback {
+        Feedback::Score
+    }
+}
+
+#[async_trait]
+impl RankOracle for Contexto {
+    async fn rank(&self, word: &str) -> Result<u32> {
+        Ok(self.play(word).await?.distance)
+    }
+
+    async fn rank_lemma(&self, word: &str) -> Result<(u32, String)> {
+        let played = self.play(word).await?;
+        Ok((played.distance, played.lemma))
+    }
+
+    fn known_lemma(&self, word: &str) -> Option<String> {
+        Contexto::known_lemma(self, word)
+    }
+}
+
+/// Ranks guesses against a known target word using the embeddings of the vector store.
+///
+/// Every word in the collection is sorted by cosine similarity to the target once at
+/// construction, so ranking a guess is a lookup and results are fully deterministic.
+pub struct LocalOracle {
+    pub target: String,
+    ranks: HashMap<String, u32>,
+}
+
+impl LocalOracle {
+    pub async fn new(store: &dyn VectorStore, target: &str) -> Result<Self> {
+        let entries = store.get_all_entries().await?;
+        Self::from_entries(&entries, target)
+    }
+
+    /// build the oracle from an already fetched vocabulary
+    pub fn from_entries(entries: &[Entry], target: &str) -> Result<Self> {
+        let target_embedding = entries
+            .iter()
+            .find(|e| e.word == target)
+            .map(|e| Array1::from_vec(e.embedding.clone()))
+            .ok_or_else(|| KontekstoError::MissingEmbedding(target.to_owned()))?;
+
+        let mut scored: Vec<(&str, f32)> = entries
+            .iter()
+            .filter(|e| e.word != target)
+            .map(|e| {
+                let sim = cosine(target_embedding.view(), ArrayView1::from(&e.embedding));
+                (e.word.as_str(), sim)
+            })
+            .collect();
+
+        // ties are broken alphabetically to keep ranks deterministic
+        scored.sort_by(|(w1, s1), (w2, s2)| s2.total_cmp(s1).then_with(|| w1.cmp(w2)));
+
+        let ranks: HashMap<String, u32> = std::iter::once(target)
+            .chain(scored.into_iter().map(|(word, _)| word))
+            .enumerate()
+            .map(|(rank, word)| (word.to_owned(), rank as u32))
+            .collect();
+
+        Ok(Self {
+            target: target.to_owned(),
+            ranks,
+        })
+    }
+
+    /// the `n` words closest to the target, the target being first
+    pub fn top(&self, n: usize) -> Vec<String> {
+        let mut ranked: Vec<(&String, &u32)> = self
+            .ranks
+            .iter()
+            .filter(|(_, rank)| (**rank as usize) < n)
+            .collect();
+        ranked.sort_by_key(|(_, rank)| **rank);
+        ranked.into_iter().map(|(word, _)| word.clone()).collect()
+    }
+}
+
+#[async_trait]
+impl RankOracle for LocalOracle {
+    async fn rank(&self, word: &str) -> Result<u32> {
+        self.ranks
+            .get(word)
+            .copied()
+            .ok_or_else(|| KontekstoError::UnknownWord(word.to_owned()))
+    }
+}
+
+/// Wraps an oracle to keep track of how many times it was queried
+pub struct CountingOracle<O> {
+    inner: O,
+    calls: AtomicUsize,
+    errors: AtomicUsize,
+}
+
+impl<O> CountingOracle<O> {
+    pub fn new(inner: O) -> Self {
+        Self {
+            inner,
+            calls: AtomicUsize::new(0),
+            errors: AtomicUsize::new(0),
+        }
+    }
+
+    /// total number of calls made, failed or not
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    /// number of calls which returned a rank
+    pub fn successes(&self) -> usize {
+        self.calls() - self.errors.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> RankOracle for CountingOracle<O> {
+    async fn rank(&self, word: &str) -> Result<u32> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        let res = self.inner.rank(word).await;
+        if res.is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        res
+    }
+
+    async fn rank_lemma(&self, word: &str) -> Result<(u32, String)> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        let res = self.inner.rank_lemma(word).await;
+        if res.is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        res
+    }
+
+    fn known_lemma(&self, word: &str) -> Option<String> {
+        self.inner.known_lemma(word)
+    }
+
+    fn feedback(&self) -> Feedback {
+        self.inner.feedback()
+    }
+}
+
+/// Answers handed out by an oracle, as collected by a `RecordingOracle`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    /// words scored, with their rank
+    pub scored: Vec<Attempt>,
+    /// `(word, lemma)` of the words scored or looked up as another form of a lemma
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lemmas: Vec<(String, String)>,
+    /// words the oracle refused to score
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<String>,
+}
+
+impl Recording {
+    pub fn extend(&mut self, other: Recording) {
+        self.scored.extend(other.scored);
+        self.lemmas.extend(other.lemmas);
+        self.rejected.extend(other.rejected);
+    }
+
+    fn lemma(&mut self, word: &str, lemma: &str) {
+        if word != lemma && !self.lemmas.iter().any(|(w, _)| w == word) {
+            self.lemmas.push((word.to_owned(), lemma.to_owned()));
+        }
+    }
+
+    fn record(&mut self, word: &str, res: &Result<(u32, String)>) {
+        match res {
+            Ok((rank, lemma)) => {
+                self.scored.push((word.to_owned(), *rank));
+                self.lemma(word, lemma);
+            }
+            Err(e) if e.is_rejected_word() => self.rejected.push(word.to_owned()),
+            Err(_) => {}
+        }
+    }
+}
+
+/// Wraps an oracle to record every answer it hands out, e.g. for solve traces
+pub struct RecordingOracle<O> {
+    inner: O,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl<O> RecordingOracle<O> {
+    /// answers are pushed to `recording`, to be drained by whoever consumes it
+    pub fn new(inner: O, recording: Arc<Mutex<Recording>>) -> Self {
+        Self { inner, recording }
+    }
+}
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> RankOracle for RecordingOracle<O> {
+    async fn rank(&self, word: &str) -> Result<u32> {
+        let res = self.inner.rank(word).await.map(|rank| (rank, word.to_owned()));
+        self.recording.lock().unwrap().record(word, &res);
+        res.map(|(rank, _)| rank)
+    }
+
+    async fn rank_lemma(&self, word: &str) -> Result<(u32, String)> {
+        let res = self.inner.rank_lemma(word).await;
+        self.recording.lock().unwrap().record(word, &res);
+        res
+    }
+
+    fn known_lemma(&self, word: &str) -> Option<String> {
+        let lemma = self.inner.known_lemma(word)?;
+        self.recording.lock().unwrap().lemma(word, &lemma);
+        Some(lemma)
+    }
+
+    fn feedback(&self) -> Feedback {
+        self.inner.feedback()
+    }
+}
+
+/// Answers with a previous recording, never touching the network.
+///
+/// Asking for a word missing from the recording means the replay took another path than
+/// the recorded run: it fails, and the word is kept as the point of divergence.
+pub struct ReplayOracle {
+    ranks: HashMap<String, u32>,
+    lemmas: HashMap<String, String>,
+    rejected: HashSet<String>,
+    feedback: Feedback,
+    diverged: Mutex<Option<String>>,
+}
+
+impl ReplayOracle {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            ranks: recording.scored.into_iter().collect(),
+            lemmas: recording.lemmas.into_iter().collect(),
+            rejected: recording.rejected.into_iter().collect(),
+            feedback: Feedback::Rank,
+            diverged: Mutex::new(None),
+        }
+    }
+
+    /// hand out the feedback of the recorded oracle, e.g. for semantle runs
+    pub fn with_feedback(mut self, feedback: Feedback) -> Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// first word asked for which the recorded run never scored
+    pub fn divergence(&self) -> Option<String> {
+        self.diverged.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl RankOracle for ReplayOracle {
+    async fn rank(&self, word: &str) -> Result<u32> {
+        Ok(self.rank_lemma(word).await?.0)
+    }
+
+    async fn rank_lemma(&self, word: &str) -> Result<(u32, String)> {
+        if self.rejected.contains(word) {
+            return Err(KontekstoError::UnknownWord(word.to_owned()));
+        }
+        let Some(rank) = self.ranks.get(word) else {
+            self.diverged
+                .lock()
+                .unwrap()
+                .get_or_insert_with(|| word.to_owned());
+            return Err(anyhow::anyhow!("replay diverged: '{}' was never scored in the recording", word).into());
+        };
+        let lemma = self.known_lemma(word).unwrap_or_else(|| word.to_owned());
+        Ok((*rank, lemma))
+    }
+
+    fn known_lemma(&self, word: &str) -> Option<String> {
+        self.lemmas.get(word).cloned()
+    }
+
+    fn feedback(&self) -> Feedback {
+        self.feedback
+    }
+}
+
+/// `v` scaled to unit length, left as is if it's zero
+pub(crate) fn normalize(v: Array1<f32>) -> Array1<f32> {
+    let norm = v.dot(&v).sqrt();
+    if norm == 0.0 {
+        return v;
+    }
+    v / norm
+}
+
+/// unit embeddings of `entries`, one row each; they must all have the dimension of the
+/// first one
+pub(crate) fn unit_rows(entries: &[Entry]) -> Result<Array2<f32>> {
+    let dim = entries.first().map_or(0, |e| e.embedding.len());
+
+    let mut embeds = Array2::<f32>::zeros((entries.len(), dim));
+    for (i, (mut row, e)) in embeds.rows_mut().into_iter().zip(entries).enumerate() {
+        if e.embedding.len() != dim {
+            return Err(KontekstoError::DimensionMismatch {
+                line: i + 1,
+                word: e.word.clone(),
+                got: e.embedding.len(),
+                expected: dim,
+            });
+        }
+        row.assign(&normalize(Array1::from_vec(e.embedding.clone())));
+    }
+    Ok(embeds)
+}
+
+pub(crate) fn cosine(a: ArrayView1<f32>, b: ArrayView1<f32>) -> f32 {
+    let norm = a.dot(&a).sqrt() * b.dot(&b).sqrt();
+    if norm == 0.0 {
+        return 0.0;
+    }
+    a.dot(&b) / norm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, embedding: [f32; 2]) -> Entry {
+        Entry {
+            word: word.to_owned(),
+            embedding: embedding.to_vec(),
+        }
+    }
+
+    fn vocabulary() -> Vec<Entry> {
+        vec![
+            entry("far", [-1.0, 0.0]),
+            entry("zebra", [1.0, 1.0]),
+            entry("target", [1.0, 0.0]),
+            entry("apple", [1.0, 1.0]),
+            entry("close", [1.0, 0.1]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn target_is_ranked_first() {
+        let oracle = LocalOracle::from_entries(&vocabulary(), "target").unwrap();
+        assert_eq!(oracle.rank("target").await.unwrap(), 0);
+        assert_eq!(oracle.rank("close").await.unwrap(), 1);
+        assert_eq!(oracle.rank("far").await.unwrap(), 4);
+        assert_eq!(oracle.top(2), vec!["target", "close"]);
+    }
+
+    #[tokio::test]
+    async fn ties_are_broken_alphabetically() {
+        let oracle = LocalOracle::from_entries(&vocabulary(), "target").unwrap();
+        assert_eq!(oracle.rank("apple").await.unwrap(), 2);
+        assert_eq!(oracle.rank("zebra").await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn words_outside_the_vocabulary_are_unknown() {
+        let oracle = LocalOracle::from_entries(&vocabulary(), "target").unwrap();
+        assert!(matches!(
+            oracle.rank("missing").await,
+            Err(KontekstoError::UnknownWord(w)) if w == "missing"
+        ));
+    }
+
+    #[test]
+    fn target_must_be_in_the_vocabulary() {
+        assert!(matches!(
+            LocalOracle::from_entries(&vocabulary(), "missing"),
+            Err(KontekstoError::MissingEmbedding(_))
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use rand::Rng;
+
+use super::{Attempt, LinearSolver, Scored, Solver, SolverState, Step};
+use crate::config::Schedule;
+use crate::errors::Result;
+use crate::oracle::RankOracle;
+
+/// Simulated annealing over the words of the collection.
+///
+/// Each step scores the unseen neighbors of the current position and proposes a move to
+/// the best of them. Better moves are always taken while worse ones are accepted with
+/// probability `exp(-delta / t)`, where `delta` is the increase in log rank and `t` follows
+/// the configured temperature schedule. This lets the walk escape bad seeds early on and
+/// settle down as it cools.
+pub struct Annealer<O> {
+    base: Solver<O>,
+    position: Option<Scored>,
+}
+
+impl<O> Annealer<O> {
+    pub fn new(base: Solver<O>) -> Self {
+        Self {
+            base,
+            position: None,
+        }
+    }
+
+    /// temperature at the current iteration
+    pub fn temperature(&self) -> f32 {
+        let settings = &self.base.state.settings;
+        let k = self.base.state.iter as f32;
+
+        match settings.schedule {
+            Schedule::Exponential => settings.temperature * settings.cooling.powf(k),
+            Schedule::Linear => {
+                let progress = k / settings.max_iters.max(1) as f32;
+                settings.temperature * (1.0 - progress).max(0.0)
+            }
+            Schedule::Logarithmic => settings.temperature / (k + 2.0).ln(),
+        }
+    }
+
+    /// metropolis criterion on log ranks, since ranks span several orders of magnitude
+    fn accept(&mut self, current: u32, proposed: u32) -> bool {
+        let delta = (proposed as f32 + 1.0).ln() - (current as f32 + 1.0).ln();
+        if delta <= 0.0 {
+            return true;
+        }
+
+        let t = self.temperature();
+        t > 0.0 && self.base.rng.random::<f32>() < (-delta / t).exp()
+    }
+}
+
+wraps_solver!(Annealer);
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> LinearSolver for Annealer<O> {
+    type Target = Vec<f32>;
+
+    async fn next_step(&mut self, query: Self::Target) -> Result<Step<Self::Target>> {
+        if !self.base.begin_step() {
+            return Ok(Step::Bailed(self.current_best()));
+        }
+
+        // blacklisted words are skipped so the walk keeps moving outwards
+        let neighbors = self.base.state.settings.neighbors;
+        let mut scored = self.base.explore(query, neighbors).await?;
+        let proposal = scored.remove(0);
+
+        let attempt = (proposal.0.clone(), proposal.2);
+
+        // early stopping
+        if self.base.record(&attempt) {
+            return Ok(Step::Done);
+        }
+
+        let accepted = match &self.position {
+            Some((_, _, current)) => self.accept(*current, proposal.2),
+            None => true,
+        };
+        if accepted {
+            self.position = Some(proposal);
+        }
+
+        let (_, position, _) = self.position.as_ref().expect("first proposal is always accepted");
+        Ok(Step::Next(attempt, position.clone()))
+    }
+
+    fn current_best(&self) -> Attempt {
+        self.base.current_best()
+    }
+
+    fn snapshot(&self) -> SolverState {
+        self.base.snapshot()
+    }
+
+    fn reset(&mut self) {
+        self.base.reset();
+        self.position = None;
+    }
+}
@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use futures::future::join_all;
+
+use super::{Attempt, LinearSolver, Scored, Solver, SolverState, Step};
+use crate::errors::Result;
+use crate::oracle::RankOracle;
+
+/// Beam search over the words of the collection.
+///
+/// The `beam_width` best words found so far form the frontier. Every step expands each
+/// of them with its `neighbors` nearest unseen words and keeps the best `beam_width`
+/// words overall, so poor trajectories are dropped as soon as better ones show up
+/// instead of being run to the end like sequential restarts.
+pub struct Beam<O> {
+    base: Solver<O>,
+    frontier: Vec<Scored>,
+}
+
+impl<O> Beam<O> {
+    pub fn new(base: Solver<O>) -> Self {
+        Self {
+            base,
+            frontier: vec![],
+        }
+    }
+
+    /// words currently on the frontier, best first
+    pub fn frontier(&self) -> Vec<Attempt> {
+        self.frontier
+            .iter()
+            .map(|(word, _, rank)| (word.clone(), *rank))
+            .collect()
+    }
+}
+
+wraps_solver!(Beam);
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> LinearSolver for Beam<O> {
+    type Target = Vec<f32>;
+
+    async fn next_step(&mut self, query: Self::Target) -> Result<Step<Self::Target>> {
+        if !self.base.begin_step() {
+            return Ok(Step::Bailed(self.current_best()));
+        }
+        let settings = &self.base.state.settings;
+        let (width, expansion) = (settings.beam_width.max(1), settings.neighbors);
+
+        let scored = match self.frontier.is_empty() {
+            // start the beam around the seed
+            true => self.base.explore(query, expansion).await?,
+            false => {
+                let base = &self.base;
+                let expanded = join_all(
+                    self.frontier
+                        .iter()
+                        .map(|(_, embedding, _)| base.query_unseen(embedding.clone(), expansion)),
+                )
+                .await;
+
+                // positions close to each other share neighbors, only score them once
+                let mut entries = vec![];
+                for res in expanded {
+                    entries.extend(res?);
+                }
+                entries.sort_by(|a, b| a.word.cmp(&b.word));
+                entries.dedup_by(|a, b| a.word == b.word);
+
+                self.base.score(entries).await?
+            }
+        };
+
+        let attempt = (scored[0].0.clone(), scored[0].2);
+
+        // early stopping
+        if self.base.record(&attempt) {
+            return Ok(Step::Done);
+        }
+
+        self.frontier.extend(scored);
+        self.frontier.sort_by_key(|(_, _, rank)| *rank);
+        self.frontier.truncate(width);
+
+        let (_, leader, _) = &self.frontier[0];
+        Ok(Step::Next(attempt, leader.clone()))
+    }
+
+    fn current_best(&self) -> Attempt {
+        self.base.current_best()
+    }
+
+    fn snapshot(&self) -> SolverState {
+        self.base.snapshot()
+    }
+
+    fn reset(&mut self) {
+        self.base.reset();
+        self.frontier.clear();
+    }
+}
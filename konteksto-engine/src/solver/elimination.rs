@@ -0,0 +1,244 @@
+use async_trait::async_trait;
+use ndarray::{Array1, Array2};
+use rand::seq::IndexedRandom;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{Attempt, LinearSolver, Solver, SolverState, Step};
+use crate::clients::VectorStore;
+use crate::errors::Result;
+use crate::oracle::{RankOracle, unit_rows};
+
+/// share of observed pairs a candidate may order the wrong way before being dropped;
+/// our embeddings are not contexto's so the true answer is never perfectly consistent
+const ALLOWED_VIOLATIONS: f32 = 0.1;
+/// below this many candidates we stop picking informative guesses and walk instead
+const MIN_CANDIDATES: usize = 10;
+/// number of candidate guesses evaluated at each step
+const PROBES: usize = 32;
+/// number of candidates used to estimate how a guess would split the set
+const SAMPLE: usize = 1000;
+
+/// The whole collection held in memory, with unit embeddings.
+///
+/// It's read-only and can be shared by every eliminator playing in the same collection,
+/// see `Eliminator::with_vocab`
+pub struct Vocab {
+    words: Vec<String>,
+    index: HashMap<String, usize>,
+    embeds: Array2<f32>,
+}
+
+impl Vocab {
+    pub async fn load(store: &dyn VectorStore) -> Result<Self> {
+        let entries = store.get_all_entries().await?;
+        let embeds = unit_rows(&entries)?;
+
+        let words: Vec<String> = entries.into_iter().map(|e| e.word).collect();
+        let index = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w.clone(), i))
+            .collect();
+
+        Ok(Self {
+            words,
+            index,
+            embeds,
+        })
+    }
+}
+
+/// Keeps the set of words that could still be the answer given every observed rank.
+///
+/// A candidate is dropped once too many pairs of guesses are ordered differently by
+/// their similarity to it than by their observed ranks. The next guess is the one
+/// minimizing the expected number of remaining candidates, falling back to the nearest
+/// neighbor of the best word when only a handful of candidates are left.
+pub struct Eliminator<O> {
+    base: Solver<O>,
+    vocab: Option<Arc<Vocab>>,
+    candidates: Vec<usize>,
+    /// pairwise ordering violations per vocab word
+    violations: Vec<u32>,
+    /// guesses as `(vocab index, rank)`
+    observations: Vec<(usize, u32)>,
+    /// similarity of every vocab word to each observed guess
+    sims: Vec<Array1<f32>>,
+}
+
+impl<O> Eliminator<O> {
+    pub fn new(base: Solver<O>) -> Self {
+        Self {
+            base,
+            vocab: None,
+            candidates: vec![],
+            violations: vec![],
+            observations: vec![],
+            sims: vec![],
+        }
+    }
+
+    /// play over an already loaded vocabulary rather than loading one of its own
+    pub fn with_vocab(mut self, vocab: Arc<Vocab>) -> Self {
+        self.vocab = Some(vocab);
+        self.restart();
+        self
+    }
+
+    /// number of words which could still be the answer
+    pub fn remaining(&self) -> usize {
+        self.candidates.len()
+    }
+
+    fn restart(&mut self) {
+        let Some(vocab) = &self.vocab else { return };
+        let n = vocab.words.len();
+        // words contexto refuses can never be the answer
+        let rejects = self.base.rejects.as_ref();
+        self.candidates = (0..n)
+            .filter(|&i| !rejects.is_some_and(|r| r.contains(&vocab.words[i])))
+            .collect();
+        self.violations = vec![0; n];
+        self.observations.clear();
+        self.sims.clear();
+    }
+
+    /// record a scored guess and drop candidates no longer consistent with it
+    fn observe(&mut self, idx: usize, rank: u32) {
+        let Some(vocab) = &self.vocab else { return };
+        let tol = self.base.state.settings.tolerance;
+
+        let col = vocab.embeds.dot(&vocab.embeds.row(idx));
+        for (&(_, prev_rank), prev_col) in self.observations.iter().zip(self.sims.iter()) {
+            for &c in self.candidates.iter() {
+                // the better ranked guess should be the more similar one
+                let violated = match rank < prev_rank {
+                    true => col[c] + tol < prev_col[c],
+                    false => prev_col[c] + tol < col[c],
+                };
+                if violated {
+                    self.violations[c] += 1;
+                }
+            }
+        }
+        self.observations.push((idx, rank));
+        self.sims.push(col);
+
+        let n = self.observations.len() as f32;
+        let allowed = (ALLOWED_VIOLATIONS * n * (n - 1.0) / 2.0).floor() as u32;
+        let violations = &self.violations;
+        self.candidates
+            .retain(|&c| c != idx && violations[c] <= allowed);
+    }
+
+    /// guess splitting the candidates most evenly w.r.t. where it would rank among
+    /// previous guesses, i.e. minimizing the expected number of survivors
+    fn pick_probe(&mut self) -> Option<usize> {
+        let vocab = self.vocab.as_ref()?;
+        if self.candidates.len() <= MIN_CANDIDATES {
+            return None;
+        }
+
+        let rng = &mut self.base.rng;
+        let sample: Vec<usize> = self
+            .candidates
+            .choose_multiple(rng, SAMPLE)
+            .copied()
+            .collect();
+        let probes: Vec<usize> = self
+            .candidates
+            .choose_multiple(rng, PROBES)
+            .copied()
+            .collect();
+
+        probes.into_iter().min_by_key(|&w| {
+            let sims_w = vocab.embeds.dot(&vocab.embeds.row(w));
+
+            let mut buckets = vec![0u64; self.observations.len() + 1];
+            for &c in sample.iter() {
+                let bucket = self.sims.iter().filter(|col| col[c] > sims_w[c]).count();
+                buckets[bucket] += 1;
+            }
+            let expected_survivors: u64 = buckets.iter().map(|b| b * b).sum();
+            (expected_survivors, self.violations[w])
+        })
+    }
+}
+
+impl<O: RankOracle + Send + Sync> Eliminator<O> {
+    async fn load_vocab(&mut self) -> Result<()> {
+        if self.vocab.is_some() {
+            return Ok(());
+        }
+
+        self.vocab = Some(Arc::new(Vocab::load(self.base.store.as_ref()).await?));
+        self.restart();
+        Ok(())
+    }
+}
+
+wraps_solver!(Eliminator);
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> LinearSolver for Eliminator<O> {
+    type Target = Vec<f32>;
+
+    async fn next_step(&mut self, query: Self::Target) -> Result<Step<Self::Target>> {
+        if !self.base.begin_step() {
+            return Ok(Step::Bailed(self.current_best()));
+        }
+        self.load_vocab().await?;
+
+        // the query is the embedding of the chosen guess, so its nearest unseen word is
+        // the guess itself; around the seed we probe a few words instead
+        let howmany = match self.observations.is_empty() {
+            true => self.base.state.settings.neighbors,
+            false => 1,
+        };
+        let scored = self.base.explore(query, howmany).await?;
+
+        let attempt = (scored[0].0.clone(), scored[0].2);
+
+        // early stopping
+        if self.base.record(&attempt) {
+            return Ok(Step::Done);
+        }
+
+        for (word, _, rank) in scored.iter() {
+            let idx = self.vocab.as_ref().and_then(|v| v.index.get(word).copied());
+            if let Some(idx) = idx {
+                self.observe(idx, *rank);
+            }
+        }
+
+        let probe = self.pick_probe();
+        let vocab = self.vocab.as_ref().expect("vocab loaded above");
+        let next = match probe {
+            Some(probe) => vocab.embeds.row(probe).to_vec(),
+            // few candidates left: walk to the neighbors of the best word
+            None => {
+                let best = &self.base.state.best.0;
+                match vocab.index.get(best) {
+                    Some(&idx) => vocab.embeds.row(idx).to_vec(),
+                    None => scored[0].1.clone(),
+                }
+            }
+        };
+
+        Ok(Step::Next(attempt, next))
+    }
+
+    fn current_best(&self) -> Attempt {
+        self.base.current_best()
+    }
+
+    fn snapshot(&self) -> SolverState {
+        self.base.snapshot()
+    }
+
+    fn reset(&mut self) {
+        self.base.reset();
+        self.restart();
+    }
+}
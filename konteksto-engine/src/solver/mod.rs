@@ -0,0 +1,798 @@
+/// `Deref` of a strategy to the base `Solver` it wraps in its `base` field, and
+/// `into_inner` to get it back
+macro_rules! wraps_solver {
+    ($strategy:ident) => {
+        impl<O> $strategy<O> {
+            pub fn into_inner(self) -> Solver<O> {
+                self.base
+            }
+        }
+
+        impl<O> std::ops::Deref for $strategy<O> {
+            type Target = Solver<O>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.base
+            }
+        }
+
+        impl<O> std::ops::DerefMut for $strategy<O> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.base
+            }
+        }
+    };
+}
+
+pub mod annealing;
+pub mod beam;
+pub mod elimination;
+pub mod swarm;
+pub mod triangulation;
+
+use crate::clients::{Entry, RejectList, VectorStore};
+use crate::clusters::{self, Clusters};
+use crate::config::{Opening, OptimizerConfig, Strategy};
+use crate::errors::{KontekstoError, Result};
+use crate::oracle::{Feedback, RankOracle, SCORE_RESOLUTION};
+use crate::{
+    clients::Contexto,
+    config::Args,
+};
+use async_trait::async_trait;
+use futures::future::join_all;
+use ndarray::{Array1, Array2, Axis};
+use rand::{SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+pub use annealing::Annealer;
+pub use beam::Beam;
+pub use elimination::{Eliminator, Vocab};
+pub use swarm::Swarm;
+pub use triangulation::Triangulator;
+
+pub type Attempt = (String, u32);
+
+/// a word scored by the oracle, along with its embedding
+pub type Scored = (String, Vec<f32>, u32);
+
+#[derive(PartialEq, PartialOrd)]
+pub enum Step<T> {
+    Done,
+    Bailed((String, u32)),
+    Next(Attempt, T),
+}
+
+#[async_trait]
+pub trait LinearSolver {
+    type Target;
+
+    async fn next_step(&mut self, prev: Self::Target) -> Result<Step<Self::Target>>;
+    fn current_best(&self) -> Attempt;
+    fn reset(&mut self);
+
+    /// copy of the solver progress, e.g. for checkpointing
+    fn snapshot(&self) -> SolverState;
+
+    /// size of the current momentum, for strategies keeping one
+    fn momentum(&self) -> f32 {
+        0.0
+    }
+}
+
+/// progress reported while solving, e.g. for logging or streaming
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveEvent {
+    /// a new seed is being explored (0-indexed)
+    Restart(usize),
+    Guess {
+        attempt: Attempt,
+        best: Attempt,
+        momentum: f32,
+    },
+    /// solver progress after each guess
+    Checkpoint(SolverState),
+    /// a step failed, e.g. contexto was down, and the restart was given up
+    Failed(String),
+}
+
+/// default observer printing progress to stdout
+pub fn print_event(event: SolveEvent) {
+    match event {
+        SolveEvent::Restart(_) => println!("\nNew seed"),
+        SolveEvent::Guess { attempt, best, .. } => println!(
+            r#"guess: ({:<12}, {:>6}), best: ({:<12}, {:>6})"#,
+            attempt.0, attempt.1, best.0, best.1
+        ),
+        SolveEvent::Checkpoint(_) => {}
+        SolveEvent::Failed(e) => println!("WARN: solver step failed: {}", e),
+    }
+}
+
+pub async fn solve<S>(seed: S::Target, solver: &mut S) -> Step<S::Target>
+where
+    S: LinearSolver,
+{
+    solve_observed(seed, solver, &mut print_event).await
+}
+
+pub async fn solve_observed<S, F>(seed: S::Target, solver: &mut S, on_event: &mut F) -> Step<S::Target>
+where
+    S: LinearSolver,
+    F: FnMut(SolveEvent),
+{
+    let mut prev = seed;
+
+    loop {
+        let step = match solver.next_step(prev).await {
+            Ok(step) => step,
+            Err(e) => {
+                on_event(SolveEvent::Failed(e.to_string()));
+                return Step::Bailed(solver.current_best());
+            }
+        };
+
+        match step {
+            Step::Next(attempt, next) => {
+                on_event(SolveEvent::Guess {
+                    attempt,
+                    best: solver.current_best(),
+                    momentum: solver.momentum(),
+                });
+                on_event(SolveEvent::Checkpoint(solver.snapshot()));
+                prev = next
+            }
+            other => return other, // Done or Bailed
+        }
+    }
+}
+
+pub async fn solve_with_restarts<S>(
+    solver: &mut S,
+    seeds: Vec<S::Target>,
+) -> Attempt
+where
+    S: LinearSolver,
+    <S as LinearSolver>::Target: PartialEq,
+{
+    solve_with_restarts_observed(solver, seeds, print_event).await
+}
+
+pub async fn solve_with_restarts_observed<S, F>(
+    solver: &mut S,
+    seeds: Vec<S::Target>,
+    mut on_event: F,
+) -> Attempt
+where
+    S: LinearSolver,
+    <S as LinearSolver>::Target: PartialEq,
+    F: FnMut(SolveEvent),
+{
+    let mut sols = vec![];
+
+    for (i, seed) in seeds.into_iter().enumerate() {
+        on_event(SolveEvent::Restart(i));
+        if solve_observed(seed, solver, &mut on_event).await == Step::Done {
+            return solver.current_best();
+        }
+
+        sols.push(solver.current_best());
+        solver.reset();
+    }
+
+    sols.sort_by_key(|entry| entry.1);
+    sols.remove(0)
+}
+
+/// Progress of a solve, serializable so it can be checkpointed and resumed.
+///
+/// Strategies keeping their own bookkeeping (observations, candidates, particles...)
+/// rebuild it after a resume, only the shared progress is kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SolverState {
+    iter: usize,
+    grad: Array1<f32>,
+    best: Attempt,
+    blacklist: Vec<String>,
+    /// words which couldn't be scored because of transient errors
+    #[serde(default)]
+    failed: Vec<String>,
+    /// lemmas of the words scored so far; contexto ranks every form of a lemma the same
+    #[serde(default)]
+    lemmas: Vec<String>,
+    settings: OptimizerConfig,
+}
+
+impl SolverState {
+    pub(crate) fn from_config(settings: OptimizerConfig) -> Self {
+        Self {
+            iter: 0,
+            grad: Array1::zeros(1),
+            best: ("init".to_string(), 30000),
+            blacklist: vec![],
+            failed: vec![],
+            lemmas: vec![],
+            settings,
+        }
+    }
+
+    /// steps taken so far
+    pub fn iteration(&self) -> usize {
+        self.iter
+    }
+
+    /// best word scored so far, none before the first one
+    pub fn best(&self) -> Option<&Attempt> {
+        Some(&self.best).filter(|(_, rank)| *rank != u32::MAX && self.iter > 0)
+    }
+
+    /// words scored or skipped so far, which won't be guessed again
+    pub fn seen(&self) -> &[String] {
+        &self.blacklist
+    }
+
+    pub fn failed(&self) -> &[String] {
+        &self.failed
+    }
+
+    pub fn settings(&self) -> &OptimizerConfig {
+        &self.settings
+    }
+}
+
+/// A struct implementing logic to solve Contexto
+pub struct Solver<O = Contexto> {
+    state: SolverState,
+    pub store: Arc<dyn VectorStore>,
+    pub oracle: O,
+    rejects: Option<Arc<RejectList>>,
+    seed: u64,
+    /// source of every random choice made by the strategies, so runs can be replayed
+    rng: StdRng,
+}
+
+impl Solver {
+    pub fn new(config: Args, store: Arc<dyn VectorStore>) -> Self {
+        let contexto = Contexto::new(config.lang, config.game_id);
+        Self::with_oracle(config, store, contexto)
+    }
+}
+
+impl<O: RankOracle> Solver<O> {
+    /// count a new step, false once `max_iters` were taken
+    fn begin_step(&mut self) -> bool {
+        if self.state.iter >= self.state.settings.max_iters {
+            return false;
+        }
+        self.state.iter += 1;
+        true
+    }
+
+    /// keep `attempt` if it's the best so far, true if it's the answer
+    fn record(&mut self, attempt: &Attempt) -> bool {
+        if attempt.1 < self.state.best.1 {
+            self.state.best = attempt.clone();
+        }
+        attempt.1 == 0
+    }
+
+    /// build a solver scoring its guesses with an arbitrary oracle
+    pub fn with_oracle(config: Args, store: Arc<dyn VectorStore>, oracle: O) -> Self {
+        let state = SolverState::from_config(config.optimizer_config);
+        let seed = config.seed.unwrap_or_else(rand::random);
+
+        Self {
+            store,
+            oracle,
+            state,
+            rejects: None,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// never guess the words in `rejects`, on top of the ones already seen
+    pub fn with_rejects(mut self, rejects: Arc<RejectList>) -> Self {
+        self.rejects = Some(rejects);
+        self
+    }
+
+    /// whether contexto is known to refuse `word`
+    pub fn is_rejected(&self, word: &str) -> bool {
+        self.rejects.as_ref().is_some_and(|r| r.contains(word))
+    }
+
+    /// every word contexto is known to refuse, sorted
+    pub fn rejects(&self) -> Vec<String> {
+        self.rejects.as_ref().map(|r| r.words()).unwrap_or_default()
+    }
+
+    /// seed the random choices of the solve are drawn from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// words the oracle failed to score because of transient errors (timeouts, rate
+    /// limiting, outages); they aren't banned and may come up again
+    pub fn failed(&self) -> &[String] {
+        &self.state.failed
+    }
+
+    pub fn settings(&self) -> &OptimizerConfig {
+        &self.state.settings
+    }
+
+    /// pick up the progress of a previous snapshot: iteration, momentum, best word and
+    /// the words seen. The current settings are kept, whatever the snapshot was taken with
+    pub fn restore(&mut self, state: SolverState) {
+        self.state = SolverState {
+            settings: self.state.settings,
+            ..state
+        };
+    }
+
+    /// swap the oracle, keeping the solver state
+    pub fn map_oracle<P>(self, f: impl FnOnce(O) -> P) -> Solver<P> {
+        Solver {
+            state: self.state,
+            store: self.store,
+            oracle: f(self.oracle),
+            rejects: self.rejects,
+            seed: self.seed,
+            rng: self.rng,
+        }
+    }
+
+    /// score a word against the current game, along with its lemma
+    async fn play(&self, word: &str) -> Result<(u32, String)> {
+        self.oracle.rank_lemma(word).await
+    }
+
+    /// whether `word` is a form of a lemma scored already, as far as we know. words
+    /// which are themselves a scored lemma count too
+    pub fn seen_lemma(&self, word: &str) -> bool {
+        let lemmas = &self.state.lemmas;
+        lemmas.iter().any(|l| l == word)
+            || self
+                .oracle
+                .known_lemma(word)
+                .is_some_and(|l| lemmas.contains(&l))
+    }
+
+    /// register the lemma of a word scored outside of the solver, e.g. by a player
+    pub fn observe_lemma(&mut self, lemma: String) {
+        if !self.state.lemmas.contains(&lemma) {
+            self.state.lemmas.push(lemma);
+        }
+    }
+
+    pub fn ban_words(&mut self, words: Vec<String>) {
+        self.state.blacklist.extend(words);
+    }
+
+    /// radomly generate seed at game start
+    pub async fn generate_seed(&self, from: u64) -> Result<Vec<f32>> {
+        let vecs = &self.store.get_random_vecs(from).await?;
+
+        let dim = vecs
+            .first()
+            .ok_or_else(|| KontekstoError::EmptyCollection(self.store.collection().to_owned()))?
+            .len();
+        let seeds = Array2::from_shape_vec(
+            (vecs.len(), dim),
+            vecs.iter()
+                .flat_map(|v| v.iter().cloned())
+                .collect(),
+        )?;
+
+        let seed = seeds
+            .mean_axis(Axis(0))
+            .ok_or_else(|| anyhow::anyhow!("failed to compute mean!"))?
+            .to_vec();
+
+        Ok(seed)
+    }
+
+    /// seeds for `n` restarts, following the configured opening
+    pub async fn generate_seeds(&mut self, n: usize) -> Result<Vec<Vec<f32>>> {
+        match self.state.settings.opening {
+            Opening::Random => {
+                let mut seeds = vec![];
+                for _ in 0..n {
+                    seeds.push(self.generate_seed(1).await?);
+                }
+                Ok(seeds)
+            }
+            Opening::Clusters => self.cluster_opening(n).await,
+        }
+    }
+
+    /// probe the representative word of every k-means cluster and return the centroids
+    /// of the `n` most promising clusters; clusters are built and cached if missing.
+    ///
+    /// Probes count as guesses of the first run: their lemmas aren't scored again and
+    /// the best of them is the best word so far
+    pub async fn cluster_opening(&mut self, n: usize) -> Result<Vec<Vec<f32>>> {
+        let k = self.state.settings.clusters;
+        let file = Clusters::sidecar(self.store.collection(), k);
+
+        let clusters = match Clusters::load(&file) {
+            Ok(clusters) => clusters,
+            Err(_) => {
+                println!("building {} clusters for '{}'", k, self.store.collection());
+                let entries = self.store.get_all_entries().await?;
+                if entries.is_empty() {
+                    return Err(KontekstoError::EmptyCollection(
+                        self.store.collection().to_owned(),
+                    ));
+                }
+                let clusters =
+                    Clusters::fit(&entries, k, clusters::DEFAULT_ITERS, &mut self.rng)?;
+                clusters.save(&file)?;
+                clusters
+            }
+        };
+
+        let probes: Vec<usize> = (0..clusters.representatives.len())
+            .filter(|&i| !self.is_rejected(&clusters.representatives[i]))
+            .collect();
+        let ranks =
+            join_all(probes.iter().map(|&i| self.play(&clusters.representatives[i]))).await;
+        let mut probed: Vec<(usize, u32)> = vec![];
+        for (&i, rank) in probes.iter().zip(ranks) {
+            match rank {
+                Ok((rank, lemma)) => {
+                    self.observe_lemma(lemma);
+                    self.record(&(clusters.representatives[i].clone(), rank));
+                    probed.push((i, rank));
+                }
+                Err(e) => println!(
+                    "WARN: failed to score '{}': {}",
+                    clusters.representatives[i], e
+                ),
+            }
+        }
+        probed.sort_by_key(|(_, rank)| *rank);
+
+        match probed.first() {
+            Some((i, rank)) => println!(
+                "opening: best cluster '{}' at {}",
+                clusters.representatives[*i], rank
+            ),
+            None => return Err(anyhow::anyhow!("no cluster could be scored").into()),
+        }
+
+        Ok(probed
+            .into_iter()
+            .take(n)
+            .map(|(i, _)| clusters.centroids[i].clone())
+            .collect())
+    }
+
+    /// retrieve nearest neighbors from embedding that have not been visited already,
+    /// nor been refused by contexto in an earlier game
+    pub async fn query_unseen(&self, embedding: Vec<f32>, howmany: u64) -> Result<Vec<Entry>> {
+        let blacklist = &self.state.blacklist;
+        let Some(rejects) = self.rejects.as_ref().filter(|r| !r.is_empty()) else {
+            return self.store.get_neighbors(embedding, howmany, blacklist).await;
+        };
+
+        // the reject list grows with every game, so rather than sending it along with the
+        // query it's filtered out here, fetching more neighbors until enough are left
+        let mut limit = howmany;
+        loop {
+            let neighbors = self
+                .store
+                .get_neighbors(embedding.clone(), limit, blacklist)
+                .await?;
+            let exhausted = (neighbors.len() as u64) < limit;
+
+            let unseen: Vec<Entry> = neighbors
+                .into_iter()
+                .filter(|e| !rejects.contains(&e.word))
+                .take(howmany as usize)
+                .collect();
+            if unseen.len() as u64 == howmany || exhausted {
+                return Ok(unseen);
+            }
+            limit *= 2;
+        }
+    }
+
+    /// score the nearest unseen neighbors of `embedding`, banning them from later queries.
+    /// when none of them can be scored, e.g. they're all forms of lemmas seen already,
+    /// more neighbors are fetched until one is, or the store runs out of unseen words.
+    /// the result is sorted from best to worst rank
+    pub async fn explore(&mut self, embedding: Vec<f32>, howmany: u64) -> Result<Vec<Scored>> {
+        let mut limit = howmany;
+        loop {
+            // explore nearby samples with blacklist
+            let neighbors = self.query_unseen(embedding.clone(), limit).await?;
+            if neighbors.is_empty() {
+                return Err(KontekstoError::Exhausted(self.store.collection().to_owned()));
+            }
+
+            let scored = self.score_all(neighbors).await?;
+            if !scored.is_empty() {
+                return Ok(scored);
+            }
+            limit *= 2;
+        }
+    }
+
+    /// score `entries` with the oracle, banning them from later queries. when none of
+    /// them can be scored, the neighbors of the first one are explored instead.
+    /// the result is sorted from best to worst rank
+    pub async fn score(&mut self, entries: Vec<Entry>) -> Result<Vec<Scored>> {
+        let Some(anchor) = entries.first().map(|e| e.embedding.clone()) else {
+            return Err(KontekstoError::Exhausted(self.store.collection().to_owned()));
+        };
+
+        let scored = self.score_all(entries).await?;
+        if !scored.is_empty() {
+            return Ok(scored);
+        }
+        let howmany = self.state.settings.neighbors;
+        self.explore(anchor, howmany).await
+    }
+
+    /// score `entries` with the oracle, banning them from later queries. the result,
+    /// sorted from best to worst rank, is empty if every entry was skipped or refused;
+    /// if none could be scored because of other errors, the last one is returned
+    async fn score_all(&mut self, entries: Vec<Entry>) -> Result<Vec<Scored>> {
+        // prevent from exploring those words next iteration (tabu-like)
+        self.ban_words(entries.iter().map(|e| e.word.clone()).collect());
+
+        // other forms of a scored lemma would only repeat its rank
+        let entries: Vec<Entry> = entries
+            .into_iter()
+            .filter(|e| !self.seen_lemma(&e.word))
+            .collect();
+
+        // get scores from contexto api
+        let ranks = join_all(entries.iter().map(|entry| self.play(&entry.word))).await;
+        let mut scored: Vec<Scored> = vec![];
+        let mut failure = None;
+        for (entry, result) in entries.into_iter().zip(ranks) {
+            match result {
+                Ok((rank, lemma)) => {
+                    self.state.failed.retain(|w| *w != entry.word);
+                    // same observation as an earlier guess, e.g. "cats" after "cat"
+                    if self.state.lemmas.contains(&lemma) {
+                        continue;
+                    }
+                    self.state.lemmas.push(lemma);
+                    scored.push((entry.word, entry.embedding, rank));
+                }
+                // words contexto won't score stay banned and are simply skipped
+                Err(e) if e.is_rejected_word() => {}
+                Err(e) => {
+                    println!("WARN: failed to score '{}': {}", entry.word, e);
+                    // give words lost to outages another chance in later queries
+                    if e.is_transient() {
+                        self.state.blacklist.retain(|w| *w != entry.word);
+                        if !self.state.failed.contains(&entry.word) {
+                            self.state.failed.push(entry.word);
+                        }
+                    }
+                    failure = Some(e);
+                }
+            }
+        }
+
+        // exploring further would most likely fail the same way
+        if let (true, Some(e)) = (scored.is_empty(), failure) {
+            return Err(e);
+        }
+
+        scored.sort_by_key(|(_, _, rank)| *rank);
+        Ok(scored)
+    }
+}
+
+/// largest step multiplier when playing with score feedback
+const MAX_SCORE_GAIN: f32 = 4.0;
+
+/// step multiplier for score feedback. ranks span orders of magnitude so their ratio
+/// makes a good gain as is, but similarities improve a little at a time and their
+/// distance vanishes near the answer, so the ratio is bounded
+fn score_gain(prev: u32, best: u32) -> f32 {
+    // nothing scored before this step
+    if prev as f32 > 2.0 * SCORE_RESOLUTION {
+        return 1.0;
+    }
+    (prev as f32 / best.max(1) as f32).clamp(1.0 / MAX_SCORE_GAIN, MAX_SCORE_GAIN)
+}
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> LinearSolver for Solver<O> {
+    type Target = Vec<f32>;
+
+    async fn next_step(&mut self, query: Self::Target) -> Result<Step<Self::Target>> {
+        if !self.begin_step() {
+            return Ok(Step::Bailed(self.current_best()));
+        }
+        let dim = query.len();
+
+        // find optimal neighbor
+        let scored_neighbors = self
+            .explore(query.clone(), self.state.settings.neighbors)
+            .await?;
+        let (best_word, best_embedding, best_rank) = &scored_neighbors[0];
+
+        let attempt = (best_word.to_owned(), *best_rank);
+        let (_, prev_rank) = self.state.best;
+
+        // if current score is worse (within a tolerance) don't update position
+        if *best_rank < self.state.best.1 {
+            self.state.best = attempt.clone();
+        } else if *best_rank > self.state.settings.margin {
+            return Ok(Step::Next(attempt, query));
+        };
+
+        // early stopping
+        if *best_rank == 0 {
+            return Ok(Step::Done);
+        }
+
+        // hill climbing with momentum
+        let origin = Array1::from_shape_vec(dim, query.clone())?;
+        let chosen = Array1::from_shape_vec(dim, best_embedding.clone())?;
+
+        let dir = &chosen - &origin;
+        let gain = match self.oracle.feedback() {
+            Feedback::Rank => prev_rank as f32 / *best_rank as f32,
+            Feedback::Score => score_gain(prev_rank, *best_rank),
+        };
+        let g = dir * gain;
+
+        let beta = self.state.settings.beta;
+        self.state.grad = beta * &self.state.grad + (1.0 - beta) * g;
+        let next_query = origin + &self.state.grad;
+
+        Ok(Step::Next(attempt, next_query.to_vec()))
+    }
+
+    fn current_best(&self) -> (String, u32) {
+        self.state.best.clone()
+    }
+
+    fn reset(&mut self) {
+        self.state.best = ("".to_string(), u32::MAX);
+        self.state.blacklist.clear();
+        self.state.failed.clear();
+        self.state.lemmas.clear();
+        self.state.grad = Array1::zeros(1);
+        self.state.iter = 0;
+    }
+
+    fn momentum(&self) -> f32 {
+        self.state.grad.dot(&self.state.grad).sqrt()
+    }
+
+    fn snapshot(&self) -> SolverState {
+        self.state.clone()
+    }
+}
+
+/// A solver running whichever strategy was picked in its config
+pub enum AnySolver<O = Contexto> {
+    HillClimb(Solver<O>),
+    Triangulation(Triangulator<O>),
+    Elimination(Eliminator<O>),
+    Annealing(Annealer<O>),
+    Swarm(Swarm<O>),
+    Beam(Beam<O>),
+}
+
+impl<O> AnySolver<O> {
+    pub fn new(solver: Solver<O>) -> Self {
+        match solver.state.settings.strategy {
+            Strategy::HillClimb => Self::HillClimb(solver),
+            Strategy::Triangulation => Self::Triangulation(Triangulator::new(solver)),
+            Strategy::Elimination => Self::Elimination(Eliminator::new(solver)),
+            Strategy::Annealing => Self::Annealing(Annealer::new(solver)),
+            Strategy::Swarm => Self::Swarm(Swarm::new(solver)),
+            Strategy::Beam => Self::Beam(Beam::new(solver)),
+        }
+    }
+
+    /// let an elimination solver share `vocab` instead of loading its own copy; other
+    /// strategies don't hold the vocabulary
+    pub fn with_vocab(self, vocab: Arc<Vocab>) -> Self {
+        match self {
+            Self::Elimination(s) => Self::Elimination(s.with_vocab(vocab)),
+            other => other,
+        }
+    }
+}
+
+impl<O> Deref for AnySolver<O> {
+    type Target = Solver<O>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::HillClimb(s) => s,
+            Self::Triangulation(s) => s,
+            Self::Elimination(s) => s,
+            Self::Annealing(s) => s,
+            Self::Swarm(s) => s,
+            Self::Beam(s) => s,
+        }
+    }
+}
+
+impl<O> DerefMut for AnySolver<O> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::HillClimb(s) => s,
+            Self::Triangulation(s) => s,
+            Self::Elimination(s) => s,
+            Self::Annealing(s) => s,
+            Self::Swarm(s) => s,
+            Self::Beam(s) => s,
+        }
+    }
+}
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> LinearSolver for AnySolver<O> {
+    type Target = Vec<f32>;
+
+    async fn next_step(&mut self, query: Self::Target) -> Result<Step<Self::Target>> {
+        match self {
+            Self::HillClimb(s) => s.next_step(query).await,
+            Self::Triangulation(s) => s.next_step(query).await,
+            Self::Elimination(s) => s.next_step(query).await,
+            Self::Annealing(s) => s.next_step(query).await,
+            Self::Swarm(s) => s.next_step(query).await,
+            Self::Beam(s) => s.next_step(query).await,
+        }
+    }
+
+    fn current_best(&self) -> Attempt {
+        match self {
+            Self::HillClimb(s) => s.current_best(),
+            Self::Triangulation(s) => s.current_best(),
+            Self::Elimination(s) => s.current_best(),
+            Self::Annealing(s) => s.current_best(),
+            Self::Swarm(s) => s.current_best(),
+            Self::Beam(s) => s.current_best(),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Self::HillClimb(s) => s.reset(),
+            Self::Triangulation(s) => s.reset(),
+            Self::Elimination(s) => s.reset(),
+            Self::Annealing(s) => s.reset(),
+            Self::Swarm(s) => s.reset(),
+            Self::Beam(s) => s.reset(),
+        }
+    }
+
+    fn momentum(&self) -> f32 {
+        match self {
+            Self::HillClimb(s) => s.momentum(),
+            Self::Triangulation(s) => s.momentum(),
+            Self::Elimination(s) => s.momentum(),
+            Self::Annealing(s) => s.momentum(),
+            Self::Swarm(s) => s.momentum(),
+            Self::Beam(s) => s.momentum(),
+        }
+    }
+
+    fn snapshot(&self) -> SolverState {
+        match self {
+            Self::HillClimb(s) => s.snapshot(),
+            Self::Triangulation(s) => s.snapshot(),
+            Self::Elimination(s) => s.snapshot(),
+            Self::Annealing(s) => s.snapshot(),
+            Self::Swarm(s) => s.snapshot(),
+            Self::Beam(s) => s.snapshot(),
+        }
+    }
+}
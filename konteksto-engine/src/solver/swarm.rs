@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use futures::future::join_all;
+use ndarray::Array1;
+use rand::Rng;
+
+use super::{Attempt, LinearSolver, Solver, SolverState, Step};
+use crate::errors::Result;
+use crate::oracle::RankOracle;
+
+/// pull towards a particle's own best position
+const COGNITIVE: f32 = 1.5;
+/// pull towards the best position found by the swarm
+const SOCIAL: f32 = 1.5;
+
+struct Particle {
+    position: Array1<f32>,
+    velocity: Array1<f32>,
+    best: Option<(Array1<f32>, u32)>,
+}
+
+/// Particle swarm optimization in embedding space.
+///
+/// The swarm keeps `population` query points; every step the nearest unseen word to each
+/// of them is scored in parallel, and particles are pulled towards both their own best
+/// word and the best word found by the whole swarm. `beta` acts as the inertia of the
+/// velocity update, like the momentum decay of the hill climber.
+pub struct Swarm<O> {
+    base: Solver<O>,
+    particles: Vec<Particle>,
+    best: Option<(Array1<f32>, u32)>,
+}
+
+impl<O> Swarm<O> {
+    pub fn new(base: Solver<O>) -> Self {
+        Self {
+            base,
+            particles: vec![],
+            best: None,
+        }
+    }
+
+    /// move every particle following the standard pso velocity rule
+    fn update(&mut self) {
+        let Some((global, _)) = &self.best else { return };
+        let inertia = self.base.state.settings.beta;
+        let rng = &mut self.base.rng;
+
+        for p in self.particles.iter_mut() {
+            let Some((own, _)) = &p.best else { continue };
+            let (r1, r2) = (rng.random::<f32>(), rng.random::<f32>());
+
+            p.velocity = inertia * &p.velocity
+                + COGNITIVE * r1 * (own - &p.position)
+                + SOCIAL * r2 * (global - &p.position);
+            p.position = &p.position + &p.velocity;
+        }
+    }
+}
+
+impl<O: RankOracle + Send + Sync> Swarm<O> {
+    /// the seed is one particle, the others start from random words of the collection:
+    /// the nearest ones to directions drawn from the solver's rng, so runs can be replayed
+    async fn spawn(&mut self, seed: Vec<f32>) -> Result<()> {
+        let population = self.base.state.settings.population.max(1);
+        let dim = seed.len();
+        let mut positions = vec![seed];
+        let mut drawn = vec![];
+        for _ in 1..population {
+            let direction: Vec<f32> = (0..dim)
+                .map(|_| self.base.rng.random_range(-1.0..1.0))
+                .collect();
+            let Some(entry) = self.base.store.get_neighbors(direction, 1, &drawn).await?.pop()
+            else {
+                break;
+            };
+            drawn.push(entry.word);
+            positions.push(entry.embedding);
+        }
+
+        self.particles = positions
+            .into_iter()
+            .map(|v| {
+                let position = Array1::from_vec(v);
+                Particle {
+                    velocity: Array1::zeros(position.len()),
+                    position,
+                    best: None,
+                }
+            })
+            .collect();
+        Ok(())
+    }
+}
+
+wraps_solver!(Swarm);
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> LinearSolver for Swarm<O> {
+    type Target = Vec<f32>;
+
+    async fn next_step(&mut self, query: Self::Target) -> Result<Step<Self::Target>> {
+        if !self.base.begin_step() {
+            return Ok(Step::Bailed(self.current_best()));
+        }
+
+        if self.particles.is_empty() {
+            self.spawn(query).await?;
+        }
+
+        // nearest unseen word to every particle, fetched in parallel
+        let base = &self.base;
+        let nearest = join_all(
+            self.particles
+                .iter()
+                .map(|p| base.query_unseen(p.position.to_vec(), 1)),
+        )
+        .await;
+        let nearest: Vec<_> = nearest
+            .into_iter()
+            .map(|res| res.map(|mut entries| entries.pop()))
+            .collect::<Result<_>>()?;
+
+        // particles may share a nearest word, only score it once
+        let mut entries: Vec<_> = nearest.iter().flatten().cloned().collect();
+        entries.sort_by(|a, b| a.word.cmp(&b.word));
+        entries.dedup_by(|a, b| a.word == b.word);
+        let scored = self.base.score(entries).await?;
+
+        for (p, entry) in self.particles.iter_mut().zip(nearest.iter()) {
+            let Some(entry) = entry else { continue };
+            let Some((_, embedding, rank)) = scored.iter().find(|(w, _, _)| *w == entry.word)
+            else {
+                continue;
+            };
+
+            if p.best.as_ref().is_none_or(|(_, r)| rank < r) {
+                p.best = Some((Array1::from_vec(embedding.clone()), *rank));
+            }
+            if self.best.as_ref().is_none_or(|(_, r)| rank < r) {
+                self.best = Some((Array1::from_vec(embedding.clone()), *rank));
+            }
+        }
+
+        let (best_word, _, best_rank) = &scored[0];
+        let attempt = (best_word.to_owned(), *best_rank);
+
+        // early stopping
+        if self.base.record(&attempt) {
+            return Ok(Step::Done);
+        }
+
+        self.update();
+
+        let (global, _) = self.best.as_ref().expect("at least one word was scored");
+        Ok(Step::Next(attempt, global.to_vec()))
+    }
+
+    fn current_best(&self) -> Attempt {
+        self.base.current_best()
+    }
+
+    fn snapshot(&self) -> SolverState {
+        self.base.snapshot()
+    }
+
+    fn reset(&mut self) {
+        self.base.reset();
+        self.particles.clear();
+        self.best = None;
+    }
+}
@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use ndarray::{Array1, Array2};
+
+use super::{Attempt, LinearSolver, Scored, Solver, SolverState, Step};
+use crate::errors::Result;
+use crate::oracle::{RankOracle, normalize};
+
+/// gradient ascent steps used to fit the target estimate after each observation
+const FIT_ITERS: usize = 200;
+const LEARNING_RATE: f32 = 0.5;
+/// scales similarity gaps in the logistic loss; cosine gaps between words are small
+const SHARPNESS: f32 = 20.0;
+
+/// Estimates the hidden target embedding from every `(word, rank)` observed so far.
+///
+/// The estimate is fit so that its cosine similarities to guessed words are ordered like
+/// their observed ranks, using a pairwise logistic ranking loss. Each step then scores the
+/// nearest unseen word to the estimate instead of walking from the best neighbor only.
+pub struct Triangulator<O> {
+    base: Solver<O>,
+    observations: Vec<Scored>,
+    estimate: Option<Array1<f32>>,
+}
+
+impl<O> Triangulator<O> {
+    pub fn new(base: Solver<O>) -> Self {
+        Self {
+            base,
+            observations: vec![],
+            estimate: None,
+        }
+    }
+
+    /// fit the target estimate to the observations, starting from `init`
+    fn fit(&self, init: Array1<f32>) -> Array1<f32> {
+        let n = self.observations.len();
+        if n < 2 {
+            return init;
+        }
+
+        // unit embeddings so dot products are cosine similarities
+        let dim = init.len();
+        let mut embeds = Array2::<f32>::zeros((n, dim));
+        for (mut row, (_, embedding, _)) in embeds.rows_mut().into_iter().zip(&self.observations) {
+            row.assign(&normalize(Array1::from_vec(embedding.clone())));
+        }
+
+        // pairs far apart in rank carry more information than those close together
+        let log_ranks: Vec<f32> = self
+            .observations
+            .iter()
+            .map(|(_, _, rank)| (*rank as f32 + 1.0).ln())
+            .collect();
+
+        let mut pairs = vec![];
+        for i in 0..n {
+            for j in 0..n {
+                if log_ranks[i] < log_ranks[j] {
+                    pairs.push((i, j, log_ranks[j] - log_ranks[i]));
+                }
+            }
+        }
+        if pairs.is_empty() {
+            return init;
+        }
+        let total_weight: f32 = pairs.iter().map(|(_, _, w)| w).sum();
+
+        let mut estimate = normalize(init);
+        for _ in 0..FIT_ITERS {
+            let sims = embeds.dot(&estimate);
+
+            // gradient of the log-likelihood that i is more similar than j w.r.t. sims
+            let mut grad_sims = Array1::<f32>::zeros(n);
+            for &(i, j, w) in pairs.iter() {
+                let z = SHARPNESS * (sims[i] - sims[j]);
+                let coef = w * SHARPNESS * sigmoid(-z);
+                grad_sims[i] += coef;
+                grad_sims[j] -= coef;
+            }
+
+            let grad = embeds.t().dot(&grad_sims) / total_weight;
+            estimate = normalize(estimate + LEARNING_RATE * grad);
+        }
+
+        estimate
+    }
+}
+
+wraps_solver!(Triangulator);
+
+#[async_trait]
+impl<O: RankOracle + Send + Sync> LinearSolver for Triangulator<O> {
+    type Target = Vec<f32>;
+
+    async fn next_step(&mut self, query: Self::Target) -> Result<Step<Self::Target>> {
+        if !self.base.begin_step() {
+            return Ok(Step::Bailed(self.current_best()));
+        }
+
+        // probe a few words around the seed, then only the single most likely word
+        let howmany = match self.observations.is_empty() {
+            true => self.base.state.settings.neighbors,
+            false => 1,
+        };
+        let scored = self.base.explore(query, howmany).await?;
+
+        let (best_word, best_embedding, best_rank) = &scored[0];
+        let attempt = (best_word.to_owned(), *best_rank);
+
+        // early stopping
+        if self.base.record(&attempt) {
+            return Ok(Step::Done);
+        }
+
+        let init = match self.estimate.take() {
+            Some(estimate) => estimate,
+            None => Array1::from_vec(best_embedding.clone()),
+        };
+        self.observations.extend(scored);
+
+        let estimate = self.fit(init);
+        self.estimate = Some(estimate.clone());
+
+        Ok(Step::Next(attempt, estimate.to_vec()))
+    }
+
+    fn current_best(&self) -> Attempt {
+        self.base.current_best()
+    }
+
+    fn snapshot(&self) -> SolverState {
+        self.base.snapshot()
+    }
+
+    fn reset(&mut self) {
+        self.base.reset();
+        self.observations.clear();
+        self.estimate = None;
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{Lang, OptimizerConfig};
+use crate::oracle::{Feedback, Recording};
+use crate::solver::{Attempt, SolveEvent};
+
+/// game being solved, along with everything a replay needs to take the same path
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TraceConfig {
+    pub lang: Lang,
+    pub game_id: u32,
+    /// set when solving offline against a known word
+    pub target: Option<String>,
+    pub optimizer: OptimizerConfig,
+    /// seed of the solver's random choices, missing in traces which predate it
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// words known to be refused when the run started
+    #[serde(default)]
+    pub rejects: Vec<String>,
+    #[serde(default)]
+    pub feedback: Feedback,
+}
+
+/// a line of a solve trace
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum TraceRecord {
+    /// always the first line
+    Config {
+        #[serde(flatten)]
+        config: TraceConfig,
+        timestamp_ms: u64,
+    },
+    /// words scored while picking the seeds, e.g. by the cluster opening
+    Opening {
+        #[serde(flatten)]
+        responses: Recording,
+        timestamp_ms: u64,
+    },
+    Restart {
+        restart: usize,
+        seed: Vec<f32>,
+        timestamp_ms: u64,
+    },
+    Step {
+        restart: usize,
+        /// 1-indexed within the restart
+        iter: usize,
+        /// every answer of the oracle during the step
+        #[serde(flatten)]
+        responses: Recording,
+        /// move chosen by the solver
+        attempt: Attempt,
+        best: Attempt,
+        momentum: f32,
+        timestamp_ms: u64,
+    },
+    /// the winning step doesn't report a move, its words are kept here
+    Finish {
+        #[serde(flatten)]
+        responses: Recording,
+        best: Attempt,
+        timestamp_ms: u64,
+    },
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Writes a solve trace as jsonl while the solver runs.
+///
+/// Answers are collected through a `RecordingOracle` sharing the writer's `recording`,
+/// so every scored or refused word ends up in the trace, not only the chosen moves.
+pub struct TraceWriter {
+    out: BufWriter<File>,
+    responses: Arc<Mutex<Recording>>,
+    seeds: Vec<Vec<f32>>,
+    restart: usize,
+    iter: usize,
+}
+
+impl TraceWriter {
+    pub fn create(file: &str) -> std::io::Result<Self> {
+        if let Some(parent) = std::path::Path::new(file).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        Ok(Self {
+            out: BufWriter::new(File::create(file)?),
+            responses: Arc::new(Mutex::new(Recording::default())),
+            seeds: vec![],
+            restart: 0,
+            iter: 0,
+        })
+    }
+
+    /// buffer to hand to a `RecordingOracle`
+    pub fn recording(&self) -> Arc<Mutex<Recording>> {
+        self.responses.clone()
+    }
+
+    /// lines are flushed one by one so an interrupted run keeps its trace
+    fn write(&mut self, record: &TraceRecord) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.out, record)?;
+        self.out.write_all(b"\n")?;
+        self.out.flush()
+    }
+
+    fn drain(&self) -> Recording {
+        std::mem::take(&mut *self.responses.lock().unwrap())
+    }
+
+    pub fn config(&mut self, config: TraceConfig) -> std::io::Result<()> {
+        self.write(&TraceRecord::Config {
+            config,
+            timestamp_ms: now_ms(),
+        })
+    }
+
+    /// record the words scored so far along with the seeds about to be solved from
+    pub fn opening(&mut self, seeds: &[Vec<f32>]) -> std::io::Result<()> {
+        self.seeds = seeds.to_vec();
+        let responses = self.drain();
+        self.write(&TraceRecord::Opening {
+            responses,
+            timestamp_ms: now_ms(),
+        })
+    }
+
+    pub fn observe(&mut self, event: SolveEvent) -> std::io::Result<()> {
+        match event {
+            SolveEvent::Restart(restart) => {
+                self.restart = restart;
+                self.iter = 0;
+                let seed = self.seeds.get(restart).cloned().unwrap_or_default();
+                self.write(&TraceRecord::Restart {
+                    restart,
+                    seed,
+                    timestamp_ms: now_ms(),
+                })
+            }
+            SolveEvent::Guess {
+                attempt,
+                best,
+                momentum,
+            } => {
+                self.iter += 1;
+                let responses = self.drain();
+                self.write(&TraceRecord::Step {
+                    restart: self.restart,
+                    iter: self.iter,
+                    responses,
+                    attempt,
+                    best,
+                    momentum,
+                    timestamp_ms: now_ms(),
+                })
+            }
+            SolveEvent::Checkpoint(_) | SolveEvent::Failed(_) => Ok(()),
+        }
+    }
+
+    pub fn finish(&mut self, best: Attempt) -> std::io::Result<()> {
+        let responses = self.drain();
+        self.write(&TraceRecord::Finish {
+            responses,
+            best,
+            timestamp_ms: now_ms(),
+        })
+    }
+}
+
+/// A solve trace read back from disk
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub records: Vec<TraceRecord>,
+}
+
+impl Trace {
+    pub fn load(file: &str) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(file)?);
+        let mut records = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records.push(serde_json::from_str(&line)?);
+        }
+
+        Ok(Self { records })
+    }
+
+    pub fn config(&self) -> Option<&TraceConfig> {
+        self.records.iter().find_map(|r| match r {
+            TraceRecord::Config { config, .. } => Some(config),
+            _ => None,
+        })
+    }
+
+    pub fn seeds(&self) -> Vec<Vec<f32>> {
+        self.records
+            .iter()
+            .filter_map(|r| match r {
+                TraceRecord::Restart { seed, .. } => Some(seed.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// every answer handed out by the oracle during the run
+    pub fn recording(&self) -> Recording {
+        let mut recording = Recording::default();
+        for record in self.records.iter() {
+            if let TraceRecord::Opening { responses, .. }
+            | TraceRecord::Step { responses, .. }
+            | TraceRecord::Finish { responses, .. } = record
+            {
+                recording.extend(responses.clone());
+            }
+        }
+        recording
+    }
+
+    /// best word of the run, if it went to the end
+    pub fn best(&self) -> Option<Attempt> {
+        self.records.iter().find_map(|r| match r {
+            TraceRecord::Finish { best, .. } => Some(best.clone()),
+            _ => None,
+        })
+    }
+
+    /// moves chosen by the solver, in order, along with their restart
+    pub fn moves(&self) -> Vec<(usize, Attempt)> {
+        self.records
+            .iter()
+            .filter_map(|r| match r {
+                TraceRecord::Step {
+                    restart, attempt, ..
+                } => Some((*restart, attempt.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use clap::Parser;
+use konteksto_engine::{Args, MemoryStore, clients::Entry};
+
+/// words spread evenly over a sphere, so every region of it is reachable by small moves
+pub fn sphere(n: usize) -> Vec<Entry> {
+    let golden = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+    (0..n)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / n as f32;
+            let r = (1.0 - y * y).sqrt();
+            let theta = golden * i as f32;
+            Entry {
+                word: format!("w{:03}", i),
+                embedding: vec![r * theta.cos(), y, r * theta.sin()],
+            }
+        })
+        .collect()
+}
+
+pub fn store(entries: Vec<Entry>) -> Arc<MemoryStore> {
+    Arc::new(MemoryStore::new("test", entries).unwrap())
+}
+
+/// engine settings as parsed from `flags`, e.g. `["--seed", "7"]`
+pub fn args(flags: &[&str]) -> Args {
+    Args::parse_from(std::iter::once("solve").chain(flags.iter().copied()))
+}
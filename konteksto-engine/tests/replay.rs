@@ -0,0 +1,97 @@
+mod common;
+
+use std::sync::Arc;
+
+use konteksto_engine::{
+    LocalOracle, Solver,
+    clients::VectorStore,
+    oracle::{RecordingOracle, ReplayOracle},
+    solver::{AnySolver, SolveEvent, solve_with_restarts_observed},
+    trace::{Trace, TraceConfig, TraceWriter},
+};
+
+const STRATEGIES: [&str; 6] = [
+    "hill-climb",
+    "triangulation",
+    "elimination",
+    "annealing",
+    "swarm",
+    "beam",
+];
+
+/// moves of the solve, as reported to the observer
+fn collect(moves: &mut Vec<(usize, (String, u32))>, restart: &mut usize, event: &SolveEvent) {
+    match event {
+        SolveEvent::Restart(i) => *restart = *i,
+        SolveEvent::Guess { attempt, .. } => moves.push((*restart, attempt.clone())),
+        _ => {}
+    }
+}
+
+/// solve offline for `target` with `strategy` and write the run to a trace
+async fn record(store: Arc<dyn VectorStore>, strategy: &str, target: &str, file: &str) {
+    let config = common::args(&["--strategy", strategy, "--seed", "7", "--max-retries", "2"]);
+    let entries = store.get_all_entries().await.unwrap();
+    let oracle = LocalOracle::from_entries(&entries, target).unwrap();
+    let solver = Solver::with_oracle(config.clone(), store.clone(), oracle);
+
+    let mut tracer = TraceWriter::create(file).unwrap();
+    tracer
+        .config(TraceConfig {
+            lang: config.lang,
+            game_id: config.game_id,
+            target: Some(target.to_owned()),
+            optimizer: config.optimizer_config,
+            seed: config.seed,
+            rejects: vec![],
+            feedback: Default::default(),
+        })
+        .unwrap();
+    let solver = solver.map_oracle(|o| RecordingOracle::new(o, tracer.recording()));
+    let mut solver = AnySolver::new(solver);
+
+    let seeds = vec![
+        store.get_embedding("w100".into()).await.unwrap(),
+        store.get_embedding("w400".into()).await.unwrap(),
+    ];
+    tracer.opening(&seeds).unwrap();
+    let best = solve_with_restarts_observed(&mut solver, seeds, |event| {
+        tracer.observe(event).unwrap();
+    })
+    .await;
+    tracer.finish(best).unwrap();
+}
+
+#[tokio::test]
+async fn replays_take_the_recorded_path() {
+    let store: Arc<dyn VectorStore> = common::store(common::sphere(500));
+
+    for strategy in STRATEGIES {
+        let file = std::env::temp_dir()
+            .join(format!("trace-{}.jsonl", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .into_owned();
+        record(store.clone(), strategy, "w250", &file).await;
+        let trace = Trace::load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        // same settings and rng seed as the recorded run, answers from the trace only
+        let recorded = trace.config().unwrap().clone();
+        let mut config = common::args(&[]);
+        config.optimizer_config = recorded.optimizer;
+        config.seed = recorded.seed;
+        let oracle = ReplayOracle::new(trace.recording()).with_feedback(recorded.feedback);
+        let mut solver = AnySolver::new(Solver::with_oracle(config, store.clone(), oracle));
+
+        let (mut moves, mut restart) = (vec![], 0);
+        let best = solve_with_restarts_observed(&mut solver, trace.seeds(), |event| {
+            collect(&mut moves, &mut restart, &event)
+        })
+        .await;
+
+        assert_eq!(solver.oracle.divergence(), None, "{} diverged", strategy);
+        assert!(!moves.is_empty(), "{} made no move", strategy);
+        assert_eq!(moves, trace.moves(), "{} took another path", strategy);
+        assert_eq!(Some(best), trace.best(), "{} found another word", strategy);
+    }
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use std::sync::Arc;
+
+use konteksto_engine::{
+    LocalOracle, Solver,
+    bench::Bench,
+    clients::VectorStore,
+    solver::{AnySolver, LinearSolver, SolveEvent, Step, solve_with_restarts_observed},
+};
+
+/// solve for `target` offline, returning the best word and every move made
+async fn solve(flags: &[&str], target: &str) -> ((String, u32), Vec<(String, u32)>) {
+    let entries = common::sphere(500);
+    let oracle = LocalOracle::from_entries(&entries, target).unwrap();
+    let store: Arc<dyn VectorStore> = common::store(entries);
+    let seed = store.get_embedding("w100".into()).await.unwrap();
+
+    let config = common::args(flags);
+    let mut solver = AnySolver::new(Solver::with_oracle(config, store, oracle));
+    let mut moves = vec![];
+    let best = solve_with_restarts_observed(&mut solver, vec![seed], |event| {
+        if let SolveEvent::Guess { attempt, .. } = event {
+            moves.push(attempt);
+        }
+    })
+    .await;
+    (best, moves)
+}
+
+#[tokio::test]
+async fn solves_offline_against_a_local_oracle() {
+    let (best, moves) = solve(&["--seed", "7"], "w010").await;
+    assert_eq!(best, ("w010".to_owned(), 0));
+    assert!(!moves.is_empty());
+}
+
+#[tokio::test]
+async fn solves_with_a_fixed_seed_are_reproducible() {
+    for strategy in ["hill-climb", "annealing", "swarm", "elimination"] {
+        let flags = ["--seed", "7", "--strategy", strategy];
+        let first = solve(&flags, "w250").await;
+        let second = solve(&flags, "w250").await;
+        assert_eq!(first, second, "{} isn't reproducible", strategy);
+    }
+}
+
+#[tokio::test]
+async fn bench_games_open_the_same_way_for_every_run() {
+    let store: Arc<dyn VectorStore> = common::store(common::sphere(500));
+    let config = common::args(&["--seed", "7", "--max-retries", "2"]);
+    let bench = Bench::with_store(config.clone(), store).await.unwrap();
+    let targets = vec!["w010".to_owned(), "w250".to_owned(), "w400".to_owned()];
+
+    let outcomes = |report: konteksto_engine::bench::BenchReport| {
+        report
+            .games
+            .into_iter()
+            .map(|g| (g.guesses, g.best_word, g.best_rank))
+            .collect::<Vec<_>>()
+    };
+    let first = outcomes(bench.run(config.optimizer_config, &targets, 2).await);
+    let second = outcomes(bench.run(config.optimizer_config, &targets, 2).await);
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn restoring_keeps_the_current_settings() {
+    let entries = common::sphere(500);
+    let store: Arc<dyn VectorStore> = common::store(entries.clone());
+    let seed = store.get_embedding("w100".into()).await.unwrap();
+
+    let oracle = LocalOracle::from_entries(&entries, "w250").unwrap();
+    let config = common::args(&["--seed", "7", "--max-iters", "5"]);
+    let mut solver = Solver::with_oracle(config, store.clone(), oracle);
+    assert!(matches!(solver.next_step(seed).await.unwrap(), Step::Next(..)));
+    let snapshot = solver.snapshot();
+
+    let oracle = LocalOracle::from_entries(&entries, "w250").unwrap();
+    let config = common::args(&["--seed", "7", "--max-iters", "9", "--beta", "0.9"]);
+    let mut resumed = Solver::with_oracle(config, store, oracle);
+    resumed.restore(snapshot.clone());
+
+    assert_eq!(resumed.settings().max_iters, 9);
+    assert_eq!(resumed.settings().beta, 0.9);
+    assert_eq!(resumed.snapshot().iteration(), snapshot.iteration());
+    assert_eq!(resumed.snapshot().best(), snapshot.best());
+}
@@ -0,0 +1,17 @@
+use clap::Parser;
+use konteksto_web::mock::{MockContexto, MockSettings};
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// contexto-compatible api computed from local embeddings, for development and demos
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let settings = MockSettings::parse();
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer())
+        .with(EnvFilter::try_from_default_env().unwrap_or("info".into()))
+        .init();
+
+    let addr = format!("{}:{}", settings.host, settings.port);
+    MockContexto::from_settings(&settings).await?.run(addr).await
+}
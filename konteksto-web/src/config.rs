@@ -1,4 +1,4 @@
-use konteksto_engine::Args;
+use konteksto_engine::{Args, config::Lang};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
@@ -50,6 +50,25 @@ pub struct ServerConfig {
     pub host: String,
     #[clap(long="web-port", default_value_t = 2049)]
     pub port: u16,
+    /// languages served, their embeddings are loaded the first time they're played;
+    /// `--lang` is the one players land on
+    #[clap(long, value_delimiter = ',', default_value = "en,pt-br,es")]
+    pub langs: Vec<Lang>,
+    /// let players host custom puzzles, each of which ranks the whole vocabulary
+    #[clap(long, env = "KONTEKSTO_ALLOW_PUZZLES")]
+    pub allow_puzzles: bool,
+    /// token required to create custom puzzles; anyone can create them if unset
+    #[clap(long, env = "KONTEKSTO_ADMIN_TOKEN")]
+    pub admin_token: Option<String>,
+    /// players kept in memory at once, each with their own solver
+    #[clap(long, default_value_t = 100)]
+    pub max_sessions: usize,
+    /// seconds of inactivity after which a player's solver is dropped from memory
+    #[clap(long, default_value_t = 1800)]
+    pub session_idle_secs: u64,
+    /// seconds a watched solver run waits for its event stream before it's stopped
+    #[clap(long, default_value_t = 10)]
+    pub watch_attach_secs: u64,
 }
 impl ServerConfig {
     pub fn addr(&self) -> String {
@@ -4,6 +4,30 @@ use sqlx::{SqlitePool, prelude::FromRow};
 pub struct Attempt {
     pub word: String,
     pub score: u32,
+    /// form contexto scored the word as, unknown for older guesses
+    pub lemma: Option<String>,
+    /// 1 for the first guess of the game
+    pub turn: u32,
+}
+
+/// game a session made guesses in, for the history of the player
+#[derive(FromRow)]
+pub struct PlayedGame {
+    pub lang: String,
+    /// see `GameId::key`
+    pub game: String,
+    pub guesses: u32,
+    pub best: u32,
+    /// unix seconds of the last guess
+    pub last_played: i64,
+}
+
+/// custom puzzle, ranked locally against `target`
+#[derive(FromRow)]
+pub struct Puzzle {
+    pub id: String,
+    pub lang: String,
+    pub target: String,
 }
 
 pub struct SqliteClient(SqlitePool);
@@ -13,27 +37,154 @@ impl SqliteClient {
         Self(pool)
     }
 
-    pub async fn register_guess(&self, word: &str, score: u32) -> sqlx::Result<()> {
-        let _ = sqlx::query!(
-            r#"INSERT INTO guesses(word, score) VALUES($1, $2)"#,
+    /// `game` is the key of the game, see `GameId::key`; returns the turn of the guess
+    pub async fn register_guess(
+        &self,
+        session: &str,
+        lang: &str,
+        game: &str,
+        word: &str,
+        lemma: &str,
+        score: u32,
+    ) -> sqlx::Result<u32> {
+        sqlx::query_scalar!(
+            r#"INSERT INTO guesses(session, lang, game, word, score, lemma, turn)
+            SELECT $1, $2, $3, $4, $5, $6, COUNT(*) + 1 FROM guesses
+            WHERE session = $1 AND lang = $2 AND game = $3
+            RETURNING turn AS "turn: u32""#,
+            session,
+            lang,
+            game,
             word,
-            score
+            score,
+            lemma
+        )
+        .fetch_one(&self.0)
+        .await
+    }
+
+    /// forget the guesses and solver progress of `session` in a game
+    pub async fn delete_game(&self, session: &str, lang: &str, game: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"DELETE FROM guesses WHERE session = $1 AND lang = $2 AND game = $3"#,
+            session,
+            lang,
+            game
+        )
+        .execute(&self.0)
+        .await?;
+        sqlx::query!(
+            r#"DELETE FROM checkpoints WHERE session = $1 AND lang = $2 AND game = $3"#,
+            session,
+            lang,
+            game
         )
         .execute(&self.0)
         .await?;
         Ok(())
     }
 
-    pub async fn all_guesses(&self) -> sqlx::Result<Vec<Attempt>> {
-        sqlx::query_as(r#"SELECT word, score FROM guesses"#)
-            .fetch_all(&self.0)
-            .await
+    /// guesses of `session` in a game, in the order they were made
+    pub async fn all_guesses(
+        &self,
+        session: &str,
+        lang: &str,
+        game: &str,
+    ) -> sqlx::Result<Vec<Attempt>> {
+        sqlx::query_as(
+            r#"SELECT word, score, lemma, turn FROM guesses
+            WHERE session = $1 AND lang = $2 AND game = $3 ORDER BY turn"#,
+        )
+        .bind(session)
+        .bind(lang)
+        .bind(game)
+        .fetch_all(&self.0)
+        .await
+    }
+
+    /// games `session` guessed in, most recently played first
+    pub async fn played_games(&self, session: &str) -> sqlx::Result<Vec<PlayedGame>> {
+        sqlx::query_as(
+            r#"SELECT lang, game, COUNT(*) AS guesses, MIN(score) AS best,
+            MAX(created_at) AS last_played FROM guesses
+            WHERE session = $1 GROUP BY lang, game ORDER BY last_played DESC"#,
+        )
+        .bind(session)
+        .fetch_all(&self.0)
+        .await
+    }
+
+    /// `state` is the json of a `SolverState`
+    pub async fn save_checkpoint(
+        &self,
+        session: &str,
+        lang: &str,
+        game: &str,
+        state: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO checkpoints(session, lang, game, state) VALUES($1, $2, $3, $4)
+            ON CONFLICT(session, lang, game) DO UPDATE SET state = excluded.state"#,
+            session,
+            lang,
+            game,
+            state
+        )
+        .execute(&self.0)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn load_checkpoint(
+        &self,
+        session: &str,
+        lang: &str,
+        game: &str,
+    ) -> sqlx::Result<Option<String>> {
+        sqlx::query_scalar!(
+            r#"SELECT state FROM checkpoints WHERE session = $1 AND lang = $2 AND game = $3"#,
+            session,
+            lang,
+            game
+        )
+        .fetch_optional(&self.0)
+        .await
     }
 
-    pub async fn delete_all_guesses(&self)->sqlx::Result<()>{
-        sqlx::query!(r#"DELETE FROM guesses"#)
+    /// remember session `id`, once it has something worth keeping
+    pub async fn create_session(&self, id: &str) -> sqlx::Result<()> {
+        sqlx::query(r#"INSERT OR IGNORE INTO sessions(id) VALUES($1)"#)
+            .bind(id)
             .execute(&self.0)
             .await?;
         Ok(())
     }
+
+    /// whether session `id` was handed out before
+    pub async fn session_exists(&self, id: &str) -> sqlx::Result<bool> {
+        let found: Option<String> = sqlx::query_scalar(r#"SELECT id FROM sessions WHERE id = $1"#)
+            .bind(id)
+            .fetch_optional(&self.0)
+            .await?;
+        Ok(found.is_some())
+    }
+
+    pub async fn create_puzzle(&self, puzzle: &Puzzle) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO puzzles(id, lang, target) VALUES($1, $2, $3)"#,
+            puzzle.id,
+            puzzle.lang,
+            puzzle.target
+        )
+        .execute(&self.0)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn load_puzzle(&self, id: &str) -> sqlx::Result<Option<Puzzle>> {
+        sqlx::query_as(r#"SELECT id, lang, target FROM puzzles WHERE id = $1"#)
+            .bind(id)
+            .fetch_optional(&self.0)
+            .await
+    }
 }
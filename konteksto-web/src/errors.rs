@@ -1,5 +1,7 @@
+use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
 use axum::response::{IntoResponse, Response};
 use http::StatusCode;
+use konteksto_engine::errors::KontekstoError;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -9,15 +11,86 @@ pub enum Error {
     Internal(#[from] anyhow::Error),
 
     #[error(transparent)]
-    SqlxError(#[from] sqlx::Error)
+    SqlxError(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Engine(#[from] KontekstoError),
+
+    #[error("puzzle '{0}' not found")]
+    PuzzleNotFound(String),
+
+    #[error("'{0}' isn't a game id nor a puzzle-<id>")]
+    BadGame(String),
+
+    #[error("'{0}' was guessed already")]
+    AlreadyGuessed(String),
+
+    #[error("language '{0}' isn't served here")]
+    LangNotFound(String),
+
+    /// the request couldn't be read, e.g. a malformed json body
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("only admins can do this")]
+    Forbidden,
+
+    #[error("puzzles can't be created on this server")]
+    PuzzlesDisabled,
+
+    #[error("too many players right now, try again later")]
+    TooManySessions,
+}
+
+impl Error {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Error::Engine(e) => match e {
+                KontekstoError::UnknownWord(_) | KontekstoError::CommonWord(_) => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                KontekstoError::GameNotFound(_) => StatusCode::NOT_FOUND,
+                KontekstoError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+                KontekstoError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            Error::PuzzleNotFound(_) | Error::LangNotFound(_) => StatusCode::NOT_FOUND,
+            Error::BadGame(_) | Error::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Error::AlreadyGuessed(_) => StatusCode::CONFLICT,
+            Error::Forbidden | Error::PuzzlesDisabled => StatusCode::FORBIDDEN,
+            Error::TooManySessions => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<JsonRejection> for Error {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::BadRequest(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for Error {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::BadRequest(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for Error {
+    fn from(rejection: PathRejection) -> Self {
+        Self::BadRequest(rejection.body_text())
+    }
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("something went wrong: {}", self),
-        )
-            .into_response()
+        // problems with the guess itself are shown to the player as is
+        let status = self.status();
+        match status {
+            StatusCode::INTERNAL_SERVER_ERROR => {
+                (status, format!("something went wrong: {}", self)).into_response()
+            }
+            _ => (status, self.to_string()).into_response(),
+        }
     }
 }
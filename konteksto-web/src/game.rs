@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use konteksto_engine::{
+    LocalOracle, RankOracle,
+    clients::{Contexto, Played},
+    config::Lang,
+    errors::Result,
+};
+use std::sync::Arc;
+
+/// contexto game players land on
+pub const DEFAULT_GAME: u32 = 42;
+
+/// game being played in the web app
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameId {
+    /// daily game of contexto.me
+    Contexto(Lang, u32),
+    /// puzzle hosted by us, see `db::Puzzle`
+    Puzzle(Lang, String),
+}
+
+impl GameId {
+    pub fn lang(&self) -> Lang {
+        match self {
+            GameId::Contexto(lang, _) | GameId::Puzzle(lang, _) => *lang,
+        }
+    }
+
+    /// prefix of the api routes of the game
+    pub fn api_stub(&self) -> String {
+        match self {
+            GameId::Contexto(lang, id) => format!("/api/{}/game/{}", lang, id),
+            GameId::Puzzle(lang, id) => format!("/api/{}/puzzle/{}", lang, id),
+        }
+    }
+
+    /// page of the game
+    pub fn page_url(&self) -> String {
+        match self {
+            GameId::Contexto(lang, id) => format!("/{}/game/{}/", lang, id),
+            GameId::Puzzle(lang, id) => format!("/{}/puzzle/{}/", lang, id),
+        }
+    }
+
+    /// same game in `lang`, or the default one for puzzles which only exist in theirs
+    pub fn in_lang(&self, lang: Lang) -> Self {
+        match self {
+            GameId::Contexto(_, id) => GameId::Contexto(lang, *id),
+            GameId::Puzzle(..) => GameId::Contexto(lang, DEFAULT_GAME),
+        }
+    }
+
+    /// what the game is stored as in the db next to its lang, the game id or
+    /// `puzzle-<id>`
+    pub fn key(&self) -> String {
+        match self {
+            GameId::Contexto(_, id) => id.to_string(),
+            GameId::Puzzle(_, id) => format!("puzzle-{}", id),
+        }
+    }
+
+    pub fn from_key(lang: Lang, key: &str) -> Option<Self> {
+        match key.strip_prefix("puzzle-") {
+            Some(id) => Some(GameId::Puzzle(lang, id.to_owned())),
+            None => key.parse().ok().map(|id| GameId::Contexto(lang, id)),
+        }
+    }
+}
+
+/// Scores the guesses of the current game, through contexto or locally against the
+/// target of a custom puzzle
+#[derive(Clone)]
+pub enum GameOracle {
+    Contexto(Contexto),
+    Puzzle {
+        lang: Lang,
+        id: String,
+        oracle: Arc<LocalOracle>,
+    },
+}
+
+impl GameOracle {
+    pub fn game(&self) -> GameId {
+        match self {
+            GameOracle::Contexto(contexto) => GameId::Contexto(contexto.lang, contexto.game_id),
+            GameOracle::Puzzle { lang, id, .. } => GameId::Puzzle(*lang, id.clone()),
+        }
+    }
+
+    pub async fn play(&self, word: &str) -> Result<Played> {
+        match self {
+            GameOracle::Contexto(contexto) => contexto.play(word).await,
+            // no lemmatizer for local rankings, every word is its own lemma
+            GameOracle::Puzzle { oracle, .. } => Ok(Played {
+                distance: oracle.rank(word).await?,
+                lemma: word.to_owned(),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl RankOracle for GameOracle {
+    async fn rank(&self, word: &str) -> Result<u32> {
+        Ok(self.play(word).await?.distance)
+    }
+
+    async fn rank_lemma(&self, word: &str) -> Result<(u32, String)> {
+        let played = self.play(word).await?;
+        Ok((played.distance, played.lemma))
+    }
+
+    fn known_lemma(&self, word: &str) -> Option<String> {
+        match self {
+            GameOracle::Contexto(contexto) => contexto.known_lemma(word),
+            GameOracle::Puzzle { .. } => None,
+        }
+    }
+}
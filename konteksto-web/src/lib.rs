@@ -1,6 +1,10 @@
 pub mod server;
 pub mod routes;
+pub mod session;
 pub mod db;
 pub mod config;
 pub mod state;
 pub mod errors;
+pub mod game;
+pub mod mock;
+pub mod watch;
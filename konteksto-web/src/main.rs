@@ -4,6 +4,7 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilte
 
 #[tokio::main(flavor="current_thread")]
 async fn main() -> anyhow::Result<()>{
+    // optimizer files are loaded per language, see `Language::load`
     let settings = Settings::parse();
     dbg!("{:?}", &settings);
 
@@ -0,0 +1,175 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use clap::Parser;
+use http::StatusCode;
+use konteksto_engine::{
+    Args, LocalOracle, RankOracle, clients::VectorStore, config::Lang, setup_store,
+};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+use tokio::{net::TcpListener, sync::Mutex};
+use tracing::info;
+
+/// answer of every game, per language and game id
+pub type Games = HashMap<Lang, HashMap<u32, String>>;
+
+/// settings of the mock contexto api
+#[derive(Debug, Parser, Clone)]
+pub struct MockSettings {
+    #[clap(long, default_value = "0.0.0.0")]
+    pub host: String,
+
+    #[clap(long = "mock-port", default_value_t = 2050)]
+    pub port: u16,
+
+    /// json file mapping languages to game ids to answers, e.g. `{"en": {"42": "cat"}}`
+    #[clap(long, default_value = "./data/mock-games.json")]
+    pub games: String,
+
+    /// number of words served by the top endpoint
+    #[clap(long, default_value_t = 500)]
+    pub top_size: usize,
+
+    /// vector store settings; embeddings are loaded for every language of `games`
+    #[clap(flatten)]
+    pub engine: Args,
+}
+
+/// json schema of the GET /{lang}/game/{id}/{word} endpoint
+#[derive(Serialize)]
+struct WordPayload {
+    distance: u32,
+    lemma: String,
+    word: String,
+}
+
+/// json schema of the GET /{lang}/top/{id} endpoint
+#[derive(Serialize)]
+struct TopPayload {
+    words: Vec<String>,
+}
+
+/// errors are reported like contexto does, as `{"error": "..."}`
+struct MockError(StatusCode, String);
+
+impl IntoResponse for MockError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({ "error": self.1 });
+        (self.0, Json(body)).into_response()
+    }
+}
+
+/// Serves the routes of the contexto api, ranking guesses with the local embeddings
+/// against a configured answer per game.
+///
+/// Games are ranked lazily, the first time they are played.
+pub struct MockContexto {
+    games: Games,
+    stores: HashMap<Lang, Arc<dyn VectorStore>>,
+    oracles: Mutex<HashMap<(Lang, u32), Arc<LocalOracle>>>,
+    top_size: usize,
+}
+
+impl MockContexto {
+    pub async fn from_settings(settings: &MockSettings) -> anyhow::Result<Self> {
+        let games: Games = serde_json::from_str(&std::fs::read_to_string(&settings.games)?)?;
+
+        let mut stores = HashMap::new();
+        for lang in games.keys() {
+            let config = Args {
+                lang: *lang,
+                ..settings.engine.clone()
+            };
+            stores.insert(*lang, setup_store(&config).await?);
+        }
+
+        Ok(Self::new(games, stores, settings.top_size))
+    }
+
+    /// mock over already loaded stores, one per language of `games`
+    pub fn new(games: Games, stores: HashMap<Lang, Arc<dyn VectorStore>>, top_size: usize) -> Self {
+        Self {
+            games,
+            stores,
+            oracles: Mutex::new(HashMap::new()),
+            top_size,
+        }
+    }
+
+    async fn oracle(&self, lang: Lang, game_id: u32) -> Result<Arc<LocalOracle>, MockError> {
+        if let Some(oracle) = self.oracles.lock().await.get(&(lang, game_id)) {
+            return Ok(oracle.clone());
+        }
+
+        let (Some(target), Some(store)) = (
+            self.games.get(&lang).and_then(|g| g.get(&game_id)),
+            self.stores.get(&lang),
+        ) else {
+            return Err(MockError(StatusCode::NOT_FOUND, "Game not found".to_string()));
+        };
+
+        // ranked without holding the lock, other games stay playable meanwhile
+        info!("ranking game {} ({}) against '{}'", game_id, lang, target);
+        let oracle = LocalOracle::new(store.as_ref(), target)
+            .await
+            .map_err(|e| MockError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        // keep the ranking of a concurrent first guess if it got there first
+        let mut oracles = self.oracles.lock().await;
+        let oracle = oracles
+            .entry((lang, game_id))
+            .or_insert_with(|| Arc::new(oracle));
+        Ok(oracle.clone())
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/{lang}/game/{id}/{word}", get(play))
+            .route("/{lang}/top/{id}", get(top))
+            .with_state(Arc::new(self))
+    }
+
+    pub async fn run(self, addr: String) -> anyhow::Result<()> {
+        let listener = TcpListener::bind(&addr).await?;
+        info!("mock contexto api listening on {}", addr);
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+/// GET `/{lang}/game/{id}/{word}`
+async fn play(
+    Path((lang, game_id, word)): Path<(Lang, u32, String)>,
+    State(mock): State<Arc<MockContexto>>,
+) -> Result<Json<WordPayload>, MockError> {
+    let oracle = mock.oracle(lang, game_id).await?;
+    let word = word.trim().to_lowercase();
+
+    // no lemmatizer here, every word is its own lemma
+    match oracle.rank(&word).await {
+        Ok(distance) => Ok(Json(WordPayload {
+            distance,
+            lemma: word.clone(),
+            word,
+        })),
+        Err(_) => Err(MockError(
+            StatusCode::NOT_FOUND,
+            format!("I'm sorry, I don't know the word '{}'", word),
+        )),
+    }
+}
+
+/// GET `/{lang}/top/{id}`
+async fn top(
+    Path((lang, game_id)): Path<(Lang, u32)>,
+    State(mock): State<Arc<MockContexto>>,
+) -> Result<Json<TopPayload>, MockError> {
+    let oracle = mock.oracle(lang, game_id).await?;
+    Ok(Json(TopPayload {
+        words: oracle.top(mock.top_size),
+    }))
+}
@@ -0,0 +1,299 @@
+use axum::extract::{FromRequest, FromRequestParts, Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use http::StatusCode;
+use konteksto_engine::{config::Lang, solver::LinearSolver};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::db::Attempt;
+use crate::errors::Error as AppError;
+use crate::game::GameId;
+use crate::session::SessionId;
+use crate::state::AppState;
+
+/// most suggestions handed out at once
+const MAX_SUGGESTIONS: u64 = 50;
+
+#[derive(OpenApi)]
+#[openapi(info(
+    title = "konteksto",
+    description = "Play contexto games and custom puzzles with the help of a solver. \
+        Guesses and solver progress belong to the session of the `konteksto_session` \
+        cookie, handed out on the first request."
+))]
+struct ApiDoc;
+
+/// `/api/v1` routes, along with their openapi document
+pub fn api_routes() -> OpenApiRouter<AppState> {
+    let v1 = OpenApiRouter::new()
+        .routes(routes!(list_guesses, play))
+        .routes(routes!(suggestions))
+        .routes(routes!(solver))
+        .routes(routes!(reset));
+    OpenApiRouter::with_openapi(ApiDoc::openapi()).nest("/api/v1", v1)
+}
+
+/// errors are reported as `{"error": "..."}`, with the status of the html routes
+pub struct ApiError(AppError);
+
+impl<E: Into<AppError>> From<E> for ApiError {
+    fn from(e: E) -> Self {
+        Self(e.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorPayload {
+            error: self.0.to_string(),
+        };
+        (self.0.status(), Json(body)).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// `Path` reporting malformed segments as `{"error": "..."}`
+#[derive(FromRequestParts)]
+#[from_request(via(Path), rejection(ApiError))]
+struct ApiPath<T>(T);
+
+/// `Query` reporting malformed parameters as `{"error": "..."}`
+#[derive(FromRequestParts)]
+#[from_request(via(Query), rejection(ApiError))]
+struct ApiQuery<T>(T);
+
+/// `Json` reporting malformed bodies as `{"error": "..."}`
+#[derive(FromRequest)]
+#[from_request(via(Json), rejection(ApiError))]
+struct ApiJson<T>(T);
+
+#[derive(Serialize, ToSchema)]
+struct ErrorPayload {
+    error: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct GuessQuery {
+    word: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GuessPayload {
+    word: String,
+    /// form of the word the game scored, every word is its own lemma in puzzles
+    lemma: Option<String>,
+    /// 0 for the answer
+    distance: u32,
+    /// 1 for the first guess of the game
+    turn: u32,
+}
+
+impl From<Attempt> for GuessPayload {
+    fn from(a: Attempt) -> Self {
+        Self {
+            word: a.word,
+            lemma: a.lemma,
+            distance: a.score,
+            turn: a.turn,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SuggestionsQuery {
+    /// how many words to suggest, at most 50
+    #[param(default = 5)]
+    limit: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SuggestionPayload {
+    word: String,
+    /// cosine similarity to the solver's pick, 1 for the pick itself
+    similarity: f32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BestPayload {
+    word: String,
+    distance: u32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SolverPayload {
+    /// steps taken so far, one per guess
+    iteration: usize,
+    /// closest word the solver scored so far
+    best: Option<BestPayload>,
+    /// word the solver would play next
+    suggestion: String,
+    /// words guessed or skipped, which won't be suggested
+    seen: usize,
+    /// words the game failed to score because of transient errors
+    failed: Vec<String>,
+    /// optimizer settings of the solver
+    #[schema(value_type = Object)]
+    settings: serde_json::Value,
+}
+
+/// game `game` of `lang`, a contexto game id or `puzzle-<id>`
+fn game_id(lang: &str, game: &str) -> ApiResult<GameId> {
+    let lang: Lang = lang
+        .parse()
+        .map_err(|_| AppError::LangNotFound(lang.to_owned()))?;
+    GameId::from_key(lang, game).ok_or_else(|| AppError::BadGame(game.to_owned()).into())
+}
+
+/// GET `api/v1/{lang}/games/{game}/guesses`
+#[utoipa::path(
+    get,
+    path = "/{lang}/games/{game}/guesses",
+    params(
+        ("lang" = String, Path, description = "en, pt-br or es"),
+        ("game" = String, Path, description = "contexto game id or puzzle-<id>"),
+    ),
+    responses(
+        (status = 200, description = "guesses in the order they were made", body = [GuessPayload]),
+        (status = 400, description = "malformed game", body = ErrorPayload),
+        (status = 404, description = "unknown language or puzzle", body = ErrorPayload),
+    )
+)]
+async fn list_guesses(
+    ApiPath((lang, game)): ApiPath<(String, String)>,
+    State(AppState(app)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+) -> ApiResult<Json<Vec<GuessPayload>>> {
+    let game = game_id(&lang, &game)?;
+    drop(app.open_game(&id, &game).await?);
+
+    let guesses = app
+        .sqlite
+        .all_guesses(&id, &game.lang().to_string(), &game.key())
+        .await?;
+    Ok(Json(guesses.into_iter().map(GuessPayload::from).collect()))
+}
+
+/// POST `api/v1/{lang}/games/{game}/guesses`
+#[utoipa::path(
+    post,
+    path = "/{lang}/games/{game}/guesses",
+    params(
+        ("lang" = String, Path, description = "en, pt-br or es"),
+        ("game" = String, Path, description = "contexto game id or puzzle-<id>"),
+    ),
+    request_body = GuessQuery,
+    responses(
+        (status = 201, description = "the word was scored", body = GuessPayload),
+        (status = 400, description = "malformed game or body", body = ErrorPayload),
+        (status = 404, description = "unknown language, game or puzzle", body = ErrorPayload),
+        (status = 409, description = "the word was guessed already", body = ErrorPayload),
+        (status = 422, description = "the game doesn't know or won't score the word", body = ErrorPayload),
+        (status = 429, description = "contexto is rate limiting us", body = ErrorPayload),
+    )
+)]
+async fn play(
+    ApiPath((lang, game)): ApiPath<(String, String)>,
+    State(AppState(app)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+    ApiJson(GuessQuery { word }): ApiJson<GuessQuery>,
+) -> ApiResult<(StatusCode, Json<GuessPayload>)> {
+    let game = game_id(&lang, &game)?;
+    let attempt = app.guess(&id, &game, word).await?;
+    Ok((StatusCode::CREATED, Json(attempt.into())))
+}
+
+/// GET `api/v1/{lang}/games/{game}/suggestions`
+#[utoipa::path(
+    get,
+    path = "/{lang}/games/{game}/suggestions",
+    params(
+        ("lang" = String, Path, description = "en, pt-br or es"),
+        ("game" = String, Path, description = "contexto game id or puzzle-<id>"),
+        SuggestionsQuery,
+    ),
+    responses(
+        (status = 200, description = "the solver's pick, then the unguessed words closest to it", body = [SuggestionPayload]),
+        (status = 400, description = "malformed game or limit", body = ErrorPayload),
+        (status = 404, description = "unknown language or puzzle", body = ErrorPayload),
+    )
+)]
+async fn suggestions(
+    ApiPath((lang, game)): ApiPath<(String, String)>,
+    ApiQuery(SuggestionsQuery { limit }): ApiQuery<SuggestionsQuery>,
+    State(AppState(app)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+) -> ApiResult<Json<Vec<SuggestionPayload>>> {
+    let game = game_id(&lang, &game)?;
+    let limit = limit.unwrap_or(5).clamp(1, MAX_SUGGESTIONS);
+
+    let suggestions = app.suggestions(&id, &game, limit).await?;
+    Ok(Json(
+        suggestions
+            .into_iter()
+            .map(|(word, similarity)| SuggestionPayload { word, similarity })
+            .collect(),
+    ))
+}
+
+/// GET `api/v1/{lang}/games/{game}/solver`
+#[utoipa::path(
+    get,
+    path = "/{lang}/games/{game}/solver",
+    params(
+        ("lang" = String, Path, description = "en, pt-br or es"),
+        ("game" = String, Path, description = "contexto game id or puzzle-<id>"),
+    ),
+    responses(
+        (status = 200, description = "progress of the session's solver", body = SolverPayload),
+        (status = 400, description = "malformed game", body = ErrorPayload),
+        (status = 404, description = "unknown language or puzzle", body = ErrorPayload),
+    )
+)]
+async fn solver(
+    ApiPath((lang, game)): ApiPath<(String, String)>,
+    State(AppState(app)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+) -> ApiResult<Json<SolverPayload>> {
+    let game = game_id(&lang, &game)?;
+    let session = app.open_game(&id, &game).await?;
+    let state = session.engine.snapshot();
+
+    Ok(Json(SolverPayload {
+        iteration: state.iteration(),
+        best: state.best().map(|(word, distance)| BestPayload {
+            word: word.clone(),
+            distance: *distance,
+        }),
+        suggestion: session.suggestion.clone(),
+        seen: state.seen().len(),
+        failed: state.failed().to_vec(),
+        settings: serde_json::to_value(state.settings()).map_err(anyhow::Error::from)?,
+    }))
+}
+
+/// DELETE `api/v1/{lang}/games/{game}`
+#[utoipa::path(
+    delete,
+    path = "/{lang}/games/{game}",
+    params(
+        ("lang" = String, Path, description = "en, pt-br or es"),
+        ("game" = String, Path, description = "contexto game id or puzzle-<id>"),
+    ),
+    responses(
+        (status = 204, description = "guesses and solver progress of the game were forgotten"),
+        (status = 400, description = "malformed game", body = ErrorPayload),
+        (status = 404, description = "unknown language or puzzle", body = ErrorPayload),
+    )
+)]
+async fn reset(
+    ApiPath((lang, game)): ApiPath<(String, String)>,
+    State(AppState(app)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+) -> ApiResult<StatusCode> {
+    let game = game_id(&lang, &game)?;
+    app.reset_game(&id, &game).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,41 +1,88 @@
-use axum::Form;
+use axum::{Extension, Form};
 use axum::extract::{Path, State};
+use axum::response::Redirect;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::{Stream, StreamExt, stream};
+use konteksto_engine::solver::SolveEvent;
 use maud::{Markup, html};
 use serde::Deserialize;
+use std::convert::Infallible;
 
-use crate::errors::Result as AppResult;
-use crate::state::AppState;
+use crate::db::Attempt;
+use crate::errors::{Error as AppError, Result as AppResult};
+use crate::game::GameId;
+use crate::routes::front::render_guess;
+use konteksto_engine::config::Lang;
+use crate::session::SessionId;
+use crate::state::{AppState, InnerState};
+use crate::watch::WatchEvent;
 
 #[derive(Debug, Deserialize)]
 pub struct PlayQuery {
     pub word: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct NewPuzzleQuery {
+    /// a random word is drawn if empty
+    pub target: Option<String>,
+    pub token: Option<String>,
+}
+
 /// POST `api/{lang}/game/{id}/play`
 pub async fn play(
-    Path(game_id): Path<u32>,
+    Path((lang, game_id)): Path<(Lang, u32)>,
     State(AppState(app_state)): State<AppState>,
+    Extension(session): Extension<SessionId>,
     Form(PlayQuery { word }): Form<PlayQuery>,
 ) -> AppResult<()> {
-    app_state.maybe_reset(game_id).await?;
-
-    let score = app_state.play(&word).await?;
-    app_state.sqlite.register_guess(&word, score).await?;
+    play_game(&app_state, &session, GameId::Contexto(lang, game_id), word).await
+}
 
-    // update recommender engine
-    app_state.notify_solver(word).await?;
+/// POST `api/{lang}/puzzle/{id}/play`
+pub async fn play_puzzle(
+    Path((lang, puzzle_id)): Path<(Lang, String)>,
+    State(AppState(app_state)): State<AppState>,
+    Extension(session): Extension<SessionId>,
+    Form(PlayQuery { word }): Form<PlayQuery>,
+) -> AppResult<()> {
+    play_game(&app_state, &session, GameId::Puzzle(lang, puzzle_id), word).await
+}
 
+async fn play_game(
+    app_state: &InnerState,
+    SessionId(id): &SessionId,
+    game: GameId,
+    word: String,
+) -> AppResult<()> {
+    app_state.guess(id, &game, word).await?;
     Ok(())
 }
 
 /// POST `api/{lang}/game/{id}/suggest`
 pub async fn suggest(
-    Path(game_id): Path<u32>,
+    Path((lang, game_id)): Path<(Lang, u32)>,
     State(AppState(app_state)): State<AppState>,
+    Extension(session): Extension<SessionId>,
 ) -> AppResult<Markup> {
-    app_state.maybe_reset(game_id).await?;
+    suggest_game(&app_state, &session, GameId::Contexto(lang, game_id)).await
+}
 
-    let suggestion = app_state.suggestion.lock().await.clone();
+/// POST `api/{lang}/puzzle/{id}/suggest`
+pub async fn suggest_puzzle(
+    Path((lang, puzzle_id)): Path<(Lang, String)>,
+    State(AppState(app_state)): State<AppState>,
+    Extension(session): Extension<SessionId>,
+) -> AppResult<Markup> {
+    suggest_game(&app_state, &session, GameId::Puzzle(lang, puzzle_id)).await
+}
+
+async fn suggest_game(
+    app_state: &InnerState,
+    SessionId(id): &SessionId,
+    game: GameId,
+) -> AppResult<Markup> {
+    let suggestion = app_state.suggestion(id, &game).await?;
 
     // swaps outer html
     Ok(html! {
@@ -47,7 +94,171 @@ pub async fn suggest(
             placeholder="type a word"
             value=(suggestion)
             hx-trigger="keydown[key==='Enter'&&!shiftKey]"
-            hx-post=(format!("/api/game/{}/play", game_id))
-            hx-on::after-request="if(event.detail.successful) window.location.reload();";
+            hx-post=(format!("{}/play", game.api_stub()))
+            hx-on::after-request="if(event.detail.successful) window.location.reload(); else document.getElementById('play-error').innerText = event.detail.xhr.responseText;";
     })
 }
+
+/// POST `api/{lang}/game/{id}/watch`
+pub async fn watch(
+    Path((lang, game_id)): Path<(Lang, u32)>,
+    State(AppState(app_state)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+) -> AppResult<Markup> {
+    let game = GameId::Contexto(lang, game_id);
+    let run_id = app_state.start_watch(&id, &game).await?;
+    Ok(watch_board(&game, &run_id))
+}
+
+/// POST `api/{lang}/puzzle/{id}/watch`
+pub async fn watch_puzzle(
+    Path((lang, puzzle_id)): Path<(Lang, String)>,
+    State(AppState(app_state)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+) -> AppResult<Markup> {
+    let game = GameId::Puzzle(lang, puzzle_id);
+    let run_id = app_state.start_watch(&id, &game).await?;
+    Ok(watch_board(&game, &run_id))
+}
+
+/// board following solver run `run_id` live, in place of the guesses
+fn watch_board(game: &GameId, run_id: &str) -> Markup {
+    let api_stub = game.api_stub();
+
+    html! {
+        div #board .guesses-container
+            hx-ext="sse"
+            sse-connect=(format!("{}/watch/{}/events", api_stub, run_id))
+            sse-close="done"
+        {
+            div .watch-controls {
+                p #watch-status sse-swap="best,done" { "the solver is warming up..." }
+                button
+                    class="button is-danger is-small"
+                    hx-post=(format!("{}/watch/stop", api_stub))
+                    hx-swap="none"
+                    {
+                        "Stop"
+                    }
+            }
+            ul #guesses sse-swap="guess" hx-swap="afterbegin" {}
+        }
+    }
+}
+
+/// GET `api/{lang}/game/{id}/watch/{run}/events`
+pub async fn watch_events(
+    Path((lang, game_id, run_id)): Path<(Lang, u32, String)>,
+    State(AppState(app_state)): State<AppState>,
+    Extension(session): Extension<SessionId>,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    watch_game(&app_state, &session, GameId::Contexto(lang, game_id), &run_id).await
+}
+
+/// GET `api/{lang}/puzzle/{id}/watch/{run}/events`
+pub async fn watch_events_puzzle(
+    Path((lang, puzzle_id, run_id)): Path<(Lang, String, String)>,
+    State(AppState(app_state)): State<AppState>,
+    Extension(session): Extension<SessionId>,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    watch_game(&app_state, &session, GameId::Puzzle(lang, puzzle_id), &run_id).await
+}
+
+/// Stream a solver run on `game` as html fragments: `guess` events for the guesses
+/// list, restarts included, `best` for the best word so far and a last `done` event.
+///
+/// Only attaches to run `run_id`: if it's over or already watched, e.g. when the browser
+/// reconnects, the stream ends right away instead of starting another run.
+async fn watch_game(
+    app_state: &InnerState,
+    SessionId(id): &SessionId,
+    game: GameId,
+    run_id: &str,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>> + use<>>> {
+    let watch = app_state.attach_watch(id, &game, run_id).await?;
+    let page_url = game.page_url();
+    let mut turn = 0;
+
+    let events = match watch {
+        Some(watch) => watch.into_stream().left_stream(),
+        None => stream::once(async { WatchEvent::Stopped }).right_stream(),
+    };
+    let events = events.flat_map(move |event| {
+        let events = match event {
+            WatchEvent::Solve(SolveEvent::Restart(i)) => vec![(
+                "guess",
+                html! { li .has-text-grey.my-2 { "restart " (i + 1) } },
+            )],
+            WatchEvent::Solve(SolveEvent::Guess { attempt, best, .. }) => {
+                turn += 1;
+                let (word, score) = attempt;
+                let guess = Attempt {
+                    word,
+                    score,
+                    lemma: None,
+                    turn,
+                };
+                vec![
+                    ("guess", render_guess(&guess)),
+                    ("best", html! { "best so far: " strong { (best.0) } " " (best.1 + 1) }),
+                ]
+            }
+            WatchEvent::Solve(SolveEvent::Checkpoint(_) | SolveEvent::Failed(_)) => vec![],
+            WatchEvent::Done((word, score)) => vec![(
+                "done",
+                html! {
+                    @if score == 0 { "solved: " } @else { "gave up, best: " }
+                    strong { (word) } " " (score + 1) " "
+                    a href=(page_url) { "back to your game" }
+                },
+            )],
+            WatchEvent::Stopped => vec![(
+                "done",
+                html! { "stopped. " a href=(page_url) { "back to your game" } },
+            )],
+        };
+        stream::iter(
+            events
+                .into_iter()
+                .map(|(name, markup)| Ok(Event::default().event(name).data(markup.into_string()))),
+        )
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// POST `api/{lang}/game/{id}/watch/stop`
+pub async fn stop_watch(
+    Path((lang, game_id)): Path<(Lang, u32)>,
+    State(AppState(app_state)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+) -> AppResult<()> {
+    app_state.stop_watch(&id, &GameId::Contexto(lang, game_id)).await
+}
+
+/// POST `api/{lang}/puzzle/{id}/watch/stop`
+pub async fn stop_watch_puzzle(
+    Path((lang, puzzle_id)): Path<(Lang, String)>,
+    State(AppState(app_state)): State<AppState>,
+    Extension(SessionId(id)): Extension<SessionId>,
+) -> AppResult<()> {
+    app_state.stop_watch(&id, &GameId::Puzzle(lang, puzzle_id)).await
+}
+
+/// POST `api/{lang}/puzzle`, creates a puzzle and opens it
+pub async fn new_puzzle(
+    Path(lang): Path<Lang>,
+    State(AppState(app_state)): State<AppState>,
+    Form(NewPuzzleQuery { target, token }): Form<NewPuzzleQuery>,
+) -> AppResult<Redirect> {
+    if !app_state.allow_puzzles {
+        return Err(AppError::PuzzlesDisabled);
+    }
+    let admin_token = app_state.admin_token.as_ref();
+    if admin_token.is_some_and(|t| token.as_ref() != Some(t)) {
+        return Err(AppError::Forbidden);
+    }
+
+    let id = app_state.create_puzzle(lang, target).await?;
+    Ok(Redirect::to(&GameId::Puzzle(lang, id).page_url()))
+}
@@ -1,90 +1,231 @@
-use crate::{db::Attempt, errors::Result as AppResult, state::AppState};
+use crate::{
+    db::{Attempt, PlayedGame},
+    errors::Result as AppResult,
+    game::GameId,
+    session::SessionId,
+    state::{AppState, InnerState},
+};
+use axum::Extension;
 use axum::extract::{Path, State};
+use konteksto_engine::config::Lang;
 use maud::{DOCTYPE, Markup, Render, html};
 
 pub async fn main(
-    Path(game_id): Path<u32>,
+    Path((lang, game_id)): Path<(Lang, u32)>,
     State(AppState(app)): State<AppState>,
+    Extension(session): Extension<SessionId>,
 ) -> AppResult<Markup> {
-    app.maybe_reset(game_id).await?;
+    game_page(&app, &session, GameId::Contexto(lang, game_id)).await
+}
+
+/// page of a custom puzzle, shared by its id
+pub async fn puzzle(
+    Path((lang, puzzle_id)): Path<(Lang, String)>,
+    State(AppState(app)): State<AppState>,
+    Extension(session): Extension<SessionId>,
+) -> AppResult<Markup> {
+    game_page(&app, &session, GameId::Puzzle(lang, puzzle_id)).await
+}
+
+async fn game_page(app: &InnerState, SessionId(id): &SessionId, game: GameId) -> AppResult<Markup> {
+    // lock released right away, the page only reads the db
+    drop(app.open_game(id, &game).await?);
 
     // get attempts ordered by score
-    let mut guesses = app.sqlite.all_guesses().await?;
+    let lang = game.lang().to_string();
+    let mut guesses = app.sqlite.all_guesses(id, &lang, &game.key()).await?;
     guesses.sort_by_key(|a| a.score);
 
-    let home = Home { game_id, guesses };
+    // other games of the player, to pick them back up
+    let mut history = app.sqlite.played_games(id).await?;
+    history.retain(|g| g.lang != lang || g.game != game.key());
+
+    let home = Home {
+        game,
+        langs: app.langs.clone(),
+        guesses,
+        history,
+    };
     Ok(home.render())
 }
 
+/// form to host a new puzzle
+pub async fn new_puzzle_page(
+    Path(lang): Path<Lang>,
+    State(AppState(app)): State<AppState>,
+) -> Markup {
+    let needs_token = app.admin_token.is_some();
+
+    page(html! {
+        h1 .title { "New puzzle" }
+        p .subtitle { "in " (lang) }
+        @if !app.allow_puzzles {
+            p { "Puzzles can't be created on this server." }
+        } @else {
+            form method="post" action=(format!("/api/{}/puzzle", lang)) {
+                .field {
+                    input .input type="text" name="target" placeholder="target word, random if empty";
+                }
+                @if needs_token {
+                    .field {
+                        input .input type="password" name="token" placeholder="admin token";
+                    }
+                }
+                button .button.is-link type="submit" { "Create" }
+            }
+        }
+    })
+}
+
+/// shared layout of every page
+fn page(content: Markup) -> Markup {
+    html! {
+        (DOCTYPE)
+        head {
+            title { "Kontektso" }
+            meta name="viewport" content="width=device-width, initial-scale=1" {}
+            script src="https://unpkg.com/htmx.org@2.0.4" {}
+            script src="https://unpkg.com/htmx-ext-sse@2.2.2/sse.js" {}
+            link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/bulma@1.0.4/css/bulma.min.css" {}
+            link rel="stylesheet" href="/public/css/app.css" {}
+        }
+        body {
+            main .section {
+                .container.has-text-centered {
+                    (content)
+                }
+            }
+        }
+    }
+}
+
 /// home page for the app
 pub struct Home {
-    game_id: u32,
+    game: GameId,
+    /// languages served, to switch between
+    langs: Vec<Lang>,
     guesses: Vec<Attempt>,
+    history: Vec<PlayedGame>,
 }
 
 impl Render for Home {
     fn render(&self) -> Markup {
-        let api_stub = format!("/api/game/{}", self.game_id);
-
-        html! {
-            (DOCTYPE)
-            head {
-                title { "Kontektso" }
-                meta name="viewport" content="width=device-width, initial-scale=1" {}
-                script src="https://unpkg.com/htmx.org@2.0.4" {}
-                link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/bulma@1.0.4/css/bulma.min.css" {}
-                link rel="stylesheet" href="/public/css/app.css" {}
+        let api_stub = self.game.api_stub();
+
+        page(html! {
+            h1 .title { "Kontektso" }
+            @if let GameId::Puzzle(_, id) = &self.game {
+                p .subtitle { "puzzle " (id) }
+            }
+
+            // language switcher
+            .buttons.has-addons.is-centered {
+                @for lang in self.langs.iter() {
+                    @if *lang == self.game.lang() {
+                        a .button.is-link.is-selected href=(self.game.page_url()) { (lang) }
+                    } @else {
+                        a .button href=(self.game.in_lang(*lang).page_url()) { (lang) }
+                    }
+                }
             }
-            body {
-                main .section {
-                    .container.has-text-centered {
-                        h1 .title { "Kontektso" }
-
-                        // Input and button
-                        div .input-container {
-                            .control.is-expanded {
-                                input
-                                    id="guess-input"
-                                    type="text"
-                                    name="word"
-                                    class="input"
-                                    placeholder="type a word"
-                                    hx-trigger="keydown[key==='Enter'&&!shiftKey]"
-                                    hx-post=(format!("{}/play", api_stub))
-                                    hx-on::after-request="if(event.detail.successful) window.location.reload();";
-                            }
-                            .control.button-control {
-                                button
-                                    class="button is-link"
-                                    hx-post=(format!("{}/suggest", api_stub))
-                                    hx-target="#guess-input"
-                                    hx-swap="outerHTML"
-                                    {
-                                        "Suggest"
-                                    }
-                            }
+
+            // Input and button
+            div .input-container {
+                .control.is-expanded {
+                    input
+                        id="guess-input"
+                        type="text"
+                        name="word"
+                        class="input"
+                        placeholder="type a word"
+                        hx-trigger="keydown[key==='Enter'&&!shiftKey]"
+                        hx-post=(format!("{}/play", api_stub))
+                        hx-on::after-request="if(event.detail.successful) window.location.reload(); else document.getElementById('play-error').innerText = event.detail.xhr.responseText;";
+                }
+                .control.button-control {
+                    button
+                        class="button is-link"
+                        hx-post=(format!("{}/suggest", api_stub))
+                        hx-target="#guess-input"
+                        hx-swap="outerHTML"
+                        {
+                            "Suggest"
+                        }
+                }
+                .control.button-control {
+                    // let the solver play on its own, streaming its guesses in place of ours
+                    button
+                        class="button"
+                        hx-post=(format!("{}/watch", api_stub))
+                        hx-target="#board"
+                        hx-swap="outerHTML"
+                        {
+                            "Watch"
                         }
+                }
+            }
+
+            // why the last guess was refused, if it was
+            p #play-error .help.is-danger {}
+
+            // Guesses list in a fixed-width div, the latest one first like contexto does
+            div #board .guesses-container {
+                @if let Some(last) = self.guesses.iter().max_by_key(|g| g.turn) {
+                    p .has-text-grey { "guesses: " (self.guesses.len()) }
+                    ul #last-guess .last-guess {
+                        (render_guess(last))
+                    }
+                }
+                ul #guesses {
+                    @for guess in self.guesses.iter() {
+                        (render_guess(guess))
+                    }
+                }
+            }
 
-                        // Guesses list in a fixed-width div
-                        div .guesses-container {
-                            ul #guesses {
-                                @for guess in self.guesses.iter() {
-                                    (render_guess(guess))
-                                }
-                            }
+            @if !self.history.is_empty() {
+                div .guesses-container {
+                    h2 .subtitle { "Your other games" }
+                    ul #history {
+                        @for played in self.history.iter() {
+                            (render_played(played))
                         }
                     }
                 }
             }
-        }
+        })
     }
 }
 
-fn render_guess(g: &Attempt) -> Markup {
+pub fn render_guess(g: &Attempt) -> Markup {
     html! {
         li .box.my-2.compact-box {
-            span .word { (g.word) }
+            span .word {
+                (g.word)
+                // contexto scored another form of the word
+                @if let Some(lemma) = g.lemma.as_ref().filter(|l| **l != g.word) {
+                    " " span .lemma.has-text-grey { "(" (lemma) ")" }
+                }
+            }
             span .score { (g.score + 1) }
         }
     }
 }
+
+fn render_played(g: &PlayedGame) -> Markup {
+    let game = g.lang.parse().ok().and_then(|lang| GameId::from_key(lang, &g.game));
+    let Some(game) = game else {
+        return html! {};
+    };
+    let name = match &game {
+        GameId::Contexto(lang, id) => format!("game #{} ({})", id, lang),
+        GameId::Puzzle(lang, id) => format!("puzzle {} ({})", id, lang),
+    };
+
+    html! {
+        li .box.my-2.compact-box {
+            a href=(game.page_url()) { (name) }
+            span .has-text-grey { (g.guesses) " guesses, best " span .score { (g.best + 1) } }
+        }
+    }
+}
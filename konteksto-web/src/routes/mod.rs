@@ -1,8 +1,13 @@
-use crate::state::AppState;
-use axum::{response::Redirect, routing::{get, post}, Router};
-use back::{play, suggest};
-use front::main;
+use crate::{game::{DEFAULT_GAME, GameId}, state::AppState};
+use axum::{extract::{Path, State}, response::Redirect, routing::{get, post}, Json, Router};
+use api::api_routes;
+use back::{
+    new_puzzle, play, play_puzzle, stop_watch, stop_watch_puzzle, suggest, suggest_puzzle, watch,
+    watch_events, watch_events_puzzle, watch_puzzle,
+};
+use front::{main, new_puzzle_page, puzzle};
 
+pub mod api;
 pub mod back;
 pub mod front;
 
@@ -10,12 +15,40 @@ pub mod front;
 pub fn get_routes() -> Router<AppState> {
     let backend_routes = Router::new()
         .route("/play", post(play))
-        .route("/suggest", post(suggest));
+        .route("/suggest", post(suggest))
+        .route("/watch", post(watch))
+        .route("/watch/{run}/events", get(watch_events))
+        .route("/watch/stop", post(stop_watch));
 
     let frontend_routes = Router::new().route("/", get(main));
 
+    let puzzle_backend_routes = Router::new()
+        .route("/play", post(play_puzzle))
+        .route("/suggest", post(suggest_puzzle))
+        .route("/watch", post(watch_puzzle))
+        .route("/watch/{run}/events", get(watch_events_puzzle))
+        .route("/watch/stop", post(stop_watch_puzzle));
+
+    let puzzle_frontend_routes = Router::new().route("/", get(puzzle));
+
+    // json api for scripts, documented at `api/v1/openapi.json`
+    let (api_routes, openapi) = api_routes().split_for_parts();
+    let api_routes = api_routes.route("/api/v1/openapi.json", get(|| async move { Json(openapi) }));
+
     Router::new()
-        .route("/", get(|| async { Redirect::permanent("/game/42/") }))
-        .nest("/game/{id}/", frontend_routes)
-        .nest("/api/game/{id}/", backend_routes)
+        .route("/", get(|state| landing(state, DEFAULT_GAME)))
+        // links from before games were served per language
+        .route("/game/{id}/", get(|state, Path(id)| landing(state, id)))
+        .nest("/{lang}/game/{id}/", frontend_routes)
+        .nest("/api/{lang}/game/{id}/", backend_routes)
+        .route("/{lang}/puzzle/new", get(new_puzzle_page))
+        .route("/api/{lang}/puzzle", post(new_puzzle))
+        .nest("/{lang}/puzzle/{id}/", puzzle_frontend_routes)
+        .nest("/api/{lang}/puzzle/{id}/", puzzle_backend_routes)
+        .merge(api_routes)
+}
+
+/// game `id` in the default language
+async fn landing(State(app): State<AppState>, id: u32) -> Redirect {
+    Redirect::temporary(&GameId::Contexto(app.default_lang(), id).page_url())
 }
@@ -1,11 +1,12 @@
 use crate::{
     config::Settings,
     routes::get_routes,
+    session::with_session,
     state::{AppState, InnerState},
 };
-use axum::Router;
+use axum::{Router, middleware};
 use tracing::error;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::net::TcpListener;
 use tower_http::services::ServeDir;
 
@@ -15,12 +16,10 @@ pub struct App {
 }
 impl App {
     pub fn new(config: Settings) -> Self {
-        // inject htmx.min.js
-        let app = Router::new()
-            .nest_service("/public", ServeDir::new("./konteksto-web/public"))
-            .merge(get_routes());
-
-        Self { app, config }
+        Self {
+            app: get_routes(),
+            config,
+        }
     }
     pub async fn run(self) -> anyhow::Result<()> {
         let listener = TcpListener::bind(self.config.server.addr()).await?;
@@ -28,11 +27,32 @@ impl App {
         let state = InnerState::from_config(&self.config).await?;
         let state = AppState(Arc::new(state));
 
+        // drop the solvers of players who left
+        let sessions = state.clone();
+        tokio::spawn(async move {
+            let period = (sessions.sessions.idle_timeout() / 4).max(Duration::from_secs(1));
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                sessions.sessions.evict_idle().await;
+            }
+        });
+
+        let app = router(self.app, state);
         #[allow(unused_variables)]
-        if let Err(e) = axum::serve(listener, self.app.with_state(state)).await {
+        if let Err(e) = axum::serve(listener, app).await {
             error!("server failed to start");
         }
 
         Ok(())
     }
 }
+
+/// every route of the app over `state`, with sessions handed out
+pub fn router(routes: Router<AppState>, state: AppState) -> Router {
+    // inject htmx.min.js; static files don't need a session
+    Router::new()
+        .nest_service("/public", ServeDir::new("./konteksto-web/public"))
+        .merge(routes.layer(middleware::from_fn_with_state(state.clone(), with_session)))
+        .with_state(state)
+}
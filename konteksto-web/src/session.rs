@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{HeaderMap, HeaderValue, header::COOKIE, header::SET_COOKIE};
+use konteksto_engine::{config::Lang, solver::AnySolver};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::{
+    errors::Error as AppError,
+    game::{GameId, GameOracle},
+    state::AppState,
+    watch::WatchRun,
+};
+
+pub const SESSION_COOKIE: &str = "konteksto_session";
+
+/// id of the browser session making the request, set by `with_session`
+#[derive(Clone, Debug)]
+pub struct SessionId(pub String);
+
+/// Everything one player works with: their own solver and suggestion.
+///
+/// Guesses and solver checkpoints are persisted per session, so an evicted session
+/// picks up where it left off when its player comes back.
+pub struct Session {
+    pub id: String,
+    /// language the solver's vocabulary is in
+    pub lang: Lang,
+    pub engine: AnySolver<GameOracle>,
+    /// game being played, none until the first page is loaded
+    pub game: Option<GameId>,
+    pub suggestion: String,
+    /// last solver run the player started, see `WatchRun`
+    pub watch: Option<WatchRun>,
+}
+
+/// a session and when it was last used
+type Entry = (Arc<Mutex<Session>>, Instant);
+
+/// whether a session can be dropped: unused for `idle_timeout`, and neither handling a
+/// request nor running a watched solve
+fn is_idle((session, last_seen): &Entry, now: Instant, idle_timeout: Duration) -> bool {
+    now - *last_seen >= idle_timeout
+        && session
+            .try_lock()
+            .is_ok_and(|s| s.watch.as_ref().is_none_or(|w| w.is_finished()))
+}
+
+/// Sessions kept in memory, evicting idle ones. When it's full of active sessions new
+/// players are turned away rather than evicting someone
+pub struct Sessions {
+    sessions: Mutex<HashMap<String, Entry>>,
+    max_sessions: usize,
+    idle_timeout: Duration,
+}
+
+impl Sessions {
+    pub fn new(max_sessions: usize, idle_timeout: Duration) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            max_sessions: max_sessions.max(1),
+            idle_timeout,
+        }
+    }
+
+    /// session `id`, built with `create` if it isn't in memory; none if every slot is
+    /// taken by an active session
+    pub async fn get_or_insert(
+        &self,
+        id: &str,
+        create: impl FnOnce() -> Session,
+    ) -> Option<Arc<Mutex<Session>>> {
+        let mut sessions = self.sessions.lock().await;
+        let now = Instant::now();
+
+        if let Some((session, last_seen)) = sessions.get_mut(id) {
+            *last_seen = now;
+            return Some(session.clone());
+        }
+
+        if sessions.len() >= self.max_sessions {
+            sessions.retain(|_, entry| !is_idle(entry, now, self.idle_timeout));
+        }
+        if sessions.len() >= self.max_sessions {
+            info!("too many active sessions, turning {} away", id);
+            return None;
+        }
+
+        let session = Arc::new(Mutex::new(create()));
+        sessions.insert(id.to_owned(), (session.clone(), now));
+        Some(session)
+    }
+
+    /// whether session `id` is in memory
+    pub async fn contains(&self, id: &str) -> bool {
+        self.sessions.lock().await.contains_key(id)
+    }
+
+    /// drop sessions idle for longer than the timeout
+    pub async fn evict_idle(&self) {
+        let mut sessions = self.sessions.lock().await;
+        let now = Instant::now();
+        let before = sessions.len();
+        sessions.retain(|_, entry| !is_idle(entry, now, self.idle_timeout));
+        if sessions.len() < before {
+            info!("evicted {} idle sessions", before - sessions.len());
+        }
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+}
+
+/// session id from the cookie header, if it looks like one we hand out
+fn session_cookie(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .flat_map(|h| h.split(';'))
+        .filter_map(|c| c.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, id)| id.to_owned())
+        .filter(|id| id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// whether `id` was handed out by this server, in memory or persisted
+async fn is_issued(state: &AppState, id: &str) -> sqlx::Result<bool> {
+    Ok(state.sessions.contains(id).await || state.sqlite.session_exists(id).await?)
+}
+
+/// Middleware handing every request a `SessionId`, setting the cookie for new visitors.
+///
+/// Ids are only ever issued here, a cookie with an id we didn't hand out gets a new one.
+/// Fresh ids live in memory until their first guess is saved, see `InnerState::guess`, so
+/// clients which never play, e.g. crawlers, leave nothing behind
+pub async fn with_session(State(state): State<AppState>, mut req: Request, next: Next) -> Response {
+    let cookie = session_cookie(req.headers());
+    let known = match &cookie {
+        Some(id) => match is_issued(&state, id).await {
+            Ok(known) => known,
+            Err(e) => return AppError::from(e).into_response(),
+        },
+        None => false,
+    };
+    let (id, fresh) = match (cookie, known) {
+        (Some(id), true) => (id, false),
+        _ => (uuid::Uuid::new_v4().simple().to_string(), true),
+    };
+    req.extensions_mut().insert(SessionId(id.clone()));
+
+    let mut res = next.run(req).await;
+    if fresh {
+        let cookie = format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age=31536000",
+            SESSION_COOKIE, id
+        );
+        if let Ok(cookie) = HeaderValue::from_str(&cookie) {
+            res.headers_mut().append(SET_COOKIE, cookie);
+        }
+    }
+    res
+}
@@ -1,13 +1,33 @@
-use crate::{config::Settings, db::SqliteClient};
+use crate::{
+    config::Settings,
+    db::{Attempt, Puzzle, SqliteClient},
+    errors::{Error as AppError, Result as AppResult},
+    game::{GameId, GameOracle},
+    session::{Session, Sessions},
+    watch::{Watch, WatchRun},
+};
 use anyhow::Result;
 use konteksto_engine::{
-    Solver,
-    clients::Contexto,
-    solver::{LinearSolver, Step},
+    Args, LocalOracle, Solver,
+    clients::{ApiClient, Contexto, Played, RejectList, ResponseCache, VectorStore},
+    config::{Lang, OptimizerConfig, Strategy},
+    errors::KontekstoError,
+    solver::{AnySolver, LinearSolver, SolverState, Step, Vocab},
+};
+use tracing::{info, warn};
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::Deref,
+    sync::Arc,
+    time::Duration,
 };
-use tracing::info;
-use std::{ops::Deref, sync::Arc};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OnceCell, OwnedMutexGuard};
+
+/// neighbors fetched per suggestion, in case the closest ones repeat a scored lemma
+const SUGGESTION_POOL: u64 = 5;
+
+/// puzzle rankings kept in memory
+const MAX_CACHED_PUZZLES: usize = 16;
 
 /// Internal state of web server handling all game logic
 #[derive(Clone)]
@@ -19,78 +39,501 @@ impl Deref for AppState {
     }
 }
 
+/// Everything needed to play in one language: its embeddings, the words contexto
+/// refused and a client for its games. Loaded the first time the language is played.
+pub struct Language {
+    /// engine settings with `lang` set to this language
+    config: Args,
+    store: Arc<dyn VectorStore>,
+    rejects: Arc<RejectList>,
+    /// client every contexto game is played with, sharing its cache and rate limit
+    contexto: Contexto,
+    /// rankings of the puzzles played lately, shared by every session
+    puzzles: Mutex<RecentPuzzles>,
+    /// whole vocabulary in memory, shared by every session of the elimination strategy
+    vocab: Option<Arc<Vocab>>,
+}
+
+impl Language {
+    async fn load(
+        config: &Args,
+        lang: Lang,
+        store: Option<Arc<dyn VectorStore>>,
+        http: ApiClient,
+        cache: Option<Arc<ResponseCache>>,
+    ) -> Result<Self> {
+        // every language plays with its own tuned settings, if `tune` wrote some
+        let optimizer_file = config.optimizer_file.clone().or_else(|| {
+            let file = OptimizerConfig::tuned_file(lang);
+            std::path::Path::new(&file).exists().then_some(file)
+        });
+        let config = Args {
+            lang,
+            optimizer_file,
+            ..config.clone()
+        }
+        .load_optimizer_file()?;
+        let store = match store {
+            Some(store) => store,
+            None => konteksto_engine::setup_store(&config).await?,
+        };
+        let rejects = konteksto_engine::setup_rejects(&config)?;
+
+        let mut contexto = Contexto::new(lang, config.game_id)
+            .with_client(http)
+            .with_rejects(rejects.clone());
+        if let Some(cache) = cache {
+            contexto = contexto.with_cache(cache);
+        }
+
+        let vocab = match config.optimizer_config.strategy {
+            Strategy::Elimination => Some(Arc::new(Vocab::load(store.as_ref()).await?)),
+            _ => None,
+        };
+
+        Ok(Self {
+            config,
+            store,
+            rejects,
+            contexto,
+            puzzles: Mutex::new(RecentPuzzles::default()),
+            vocab,
+        })
+    }
+
+    /// fresh solver for a session, over the vocabulary of this language
+    fn solver(&self, oracle: GameOracle) -> AnySolver<GameOracle> {
+        let solver = Solver::with_oracle(self.config.clone(), self.store.clone(), oracle)
+            .with_rejects(self.rejects.clone());
+        match &self.vocab {
+            Some(vocab) => AnySolver::new(solver).with_vocab(vocab.clone()),
+            None => AnySolver::new(solver),
+        }
+    }
+}
+
+/// Rankings of the last puzzles played, the least recently played one goes first
+#[derive(Default)]
+struct RecentPuzzles {
+    oracles: HashMap<String, Arc<LocalOracle>>,
+    /// puzzle ids, least recently played first
+    order: VecDeque<String>,
+}
+
+impl RecentPuzzles {
+    fn get(&mut self, id: &str) -> Option<Arc<LocalOracle>> {
+        let oracle = self.oracles.get(id)?.clone();
+        self.touch(id);
+        Some(oracle)
+    }
+
+    fn touch(&mut self, id: &str) {
+        if let Some(pos) = self.order.iter().position(|p| p == id) {
+            let id = self.order.remove(pos).expect("position is in bounds");
+            self.order.push_back(id);
+        }
+    }
+
+    /// keep `oracle` unless another session ranked the puzzle meanwhile
+    fn get_or_insert(&mut self, id: &str, oracle: Arc<LocalOracle>) -> Arc<LocalOracle> {
+        if let Some(oracle) = self.get(id) {
+            return oracle;
+        }
+
+        if self.order.len() >= MAX_CACHED_PUZZLES
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.oracles.remove(&oldest);
+        }
+        self.order.push_back(id.to_owned());
+        self.oracles.insert(id.to_owned(), oracle.clone());
+        oracle
+    }
+}
+
 pub struct InnerState {
     pub sqlite: SqliteClient,
-    pub engine: Mutex<Solver>,
-    pub contexto_api: Mutex<Contexto>,
-    pub suggestion: Mutex<String>,
+    pub sessions: Sessions,
+    /// settings every language is loaded with
+    engine_config: Args,
+    /// languages players can pick from, the first one is where they land
+    pub langs: Vec<Lang>,
+    /// one cell per served language, filled the first time it is played
+    languages: HashMap<Lang, OnceCell<Arc<Language>>>,
+    /// vector stores handed over up front, e.g. in tests; the others are set up from
+    /// the engine config
+    stores: HashMap<Lang, Arc<dyn VectorStore>>,
+    /// client every language calls contexto through, so they share one rate limit
+    http: ApiClient,
+    /// contexto responses of every language
+    cache: Option<Arc<ResponseCache>>,
+    /// whether puzzles can be created at all
+    pub allow_puzzles: bool,
+    /// required to create puzzles, anyone can if unset
+    pub admin_token: Option<String>,
+    /// how long watched runs wait for their event stream
+    watch_grace: Duration,
 }
 impl InnerState {
     pub async fn from_config(config: &Settings) -> Result<Self> {
+        Self::with_stores(config, HashMap::new()).await
+    }
+
+    /// state playing over `stores` rather than setting up a store for their languages
+    pub async fn with_stores(
+        config: &Settings,
+        stores: HashMap<Lang, Arc<dyn VectorStore>>,
+    ) -> Result<Self> {
         let pool = config.db.create_pool().await?;
+        let sqlite = SqliteClient::new(pool);
 
-        // spin up the application logic; build qdrant indexes if they don't already
-        let engine = konteksto_engine::setup(config.engine.clone()).await?;
-        let contexto_api = engine.contexto.clone();
+        let sessions = Sessions::new(
+            config.server.max_sessions,
+            Duration::from_secs(config.server.session_idle_secs),
+        );
 
-        // random seed for suggestion
-        let random_vec = engine.generate_seed(1).await?;
-        let random_word = engine.qdrant.get_word(random_vec).await?;
-        info!("random: {}", &random_word);
+        let cache = match config.engine.no_cache {
+            true => None,
+            false => Some(Arc::new(ResponseCache::open(config.engine.cache_path())?)),
+        };
 
-        Ok(Self {
-            sqlite: SqliteClient::new(pool),
-            engine: Mutex::new(engine),
-            contexto_api: Mutex::new(contexto_api),
-            suggestion: Mutex::new(random_word),
+        let http = ApiClient::new(config.engine.client_config.clone())?;
+
+        let default_lang = config.engine.lang;
+        let mut langs = vec![default_lang];
+        langs.extend(config.server.langs.iter().filter(|l| **l != default_lang));
+
+        let state = Self {
+            sqlite,
+            sessions,
+            engine_config: config.engine.clone(),
+            languages: langs.iter().map(|l| (*l, OnceCell::new())).collect(),
+            langs,
+            stores,
+            http,
+            cache,
+            allow_puzzles: config.server.allow_puzzles,
+            admin_token: config.server.admin_token.clone(),
+            watch_grace: Duration::from_secs(config.server.watch_attach_secs),
+        };
+
+        // spin up the application logic for the default language, the others are
+        // loaded when first played
+        state.language(default_lang).await?;
+        Ok(state)
+    }
+
+    /// language players land on
+    pub fn default_lang(&self) -> Lang {
+        self.langs[0]
+    }
+
+    /// resources of `lang`, loading its vector store the first time
+    async fn language(&self, lang: Lang) -> AppResult<Arc<Language>> {
+        let cell = self
+            .languages
+            .get(&lang)
+            .ok_or_else(|| AppError::LangNotFound(lang.to_string()))?;
+
+        // concurrent players of a language being loaded wait for that load only, the
+        // other languages stay available
+        let language = cell
+            .get_or_try_init(|| async {
+                info!("loading language {}", lang);
+                let store = self.stores.get(&lang).cloned();
+                let (http, cache) = (self.http.clone(), self.cache.clone());
+                let language =
+                    Language::load(&self.engine_config, lang, store, http, cache).await?;
+                AppResult::Ok(Arc::new(language))
+            })
+            .await?;
+        Ok(language.clone())
+    }
+
+    /// session `id`, created with a fresh solver in `lang` if it isn't in memory
+    async fn session(&self, id: &str, lang: Lang) -> AppResult<Arc<Mutex<Session>>> {
+        let language = self.language(lang).await?;
+        let session = self
+            .sessions
+            .get_or_insert(id, || Session {
+                id: id.to_owned(),
+                lang,
+                engine: language.solver(GameOracle::Contexto(language.contexto.clone())),
+                game: None,
+                suggestion: String::new(),
+                watch: None,
+            })
+            .await
+            .ok_or(AppError::TooManySessions)?;
+        Ok(session)
+    }
+
+    /// session `id`, locked and switched to `game`
+    pub async fn open_game(&self, id: &str, game: &GameId) -> AppResult<OwnedMutexGuard<Session>> {
+        let session = self.session(id, game.lang()).await?;
+        let mut session = session.lock_owned().await;
+        self.maybe_reset(&mut session, game).await?;
+        Ok(session)
+    }
+
+    /// score `word` in `game` for session `id`, record it and step the solver
+    pub async fn guess(&self, id: &str, game: &GameId, word: String) -> AppResult<Attempt> {
+        let mut session = self.open_game(id, game).await?;
+
+        let played = self.play(&session, &word).await?;
+        // sessions are only persisted once they have a guess to keep
+        self.sqlite.create_session(id).await?;
+        let turn = self
+            .sqlite
+            .register_guess(
+                id,
+                &game.lang().to_string(),
+                &game.key(),
+                &word,
+                &played.lemma,
+                played.distance,
+            )
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    AppError::AlreadyGuessed(word.clone())
+                }
+                e => e.into(),
+            })?;
+
+        // update recommender engine. the guess is recorded already, so a failing step
+        // (e.g. contexto going down) only keeps the previous suggestion around
+        if let Err(e) = self
+            .notify_solver(&mut session, word.clone(), played.lemma.clone())
+            .await
+        {
+            warn!("session {} failed to step the solver after '{}': {}", id, word, e);
+        }
+
+        Ok(Attempt {
+            word,
+            score: played.distance,
+            lemma: Some(played.lemma),
+            turn,
         })
     }
 
-    /// manual reset
-    pub async fn maybe_reset(&self, game_id: u32) -> Result<()> {
-        let engine = &mut self.engine.lock().await;
+    /// next word the solver suggests to session `id` in `game`
+    pub async fn suggestion(&self, id: &str, game: &GameId) -> AppResult<String> {
+        Ok(self.open_game(id, game).await?.suggestion.clone())
+    }
 
-        if game_id != engine.contexto.game_id {
-            info!("updating to game {}", game_id);
+    /// Up to `n` unseen words closest to the solver's suggestion, with their cosine
+    /// similarity to it; the suggestion itself comes first
+    pub async fn suggestions(
+        &self,
+        id: &str,
+        game: &GameId,
+        n: u64,
+    ) -> AppResult<Vec<(String, f32)>> {
+        let session = self.open_game(id, game).await?;
+        let engine = &session.engine;
+        let Some(anchor) = engine.store.get_embedding(session.suggestion.clone()).await else {
+            return Ok(vec![]);
+        };
 
-            // solver
-            engine.reset();
+        // embeddings are unit-normed, their dot product is the cosine similarity
+        let neighbors = engine.query_unseen(anchor.clone(), n * SUGGESTION_POOL).await?;
+        Ok(neighbors
+            .into_iter()
+            .filter(|e| !engine.seen_lemma(&e.word))
+            .take(n as usize)
+            .map(|e| {
+                let similarity = e.embedding.iter().zip(&anchor).map(|(a, b)| a * b).sum();
+                (e.word, similarity)
+            })
+            .collect())
+    }
 
-            // api 
-            let contexto = Contexto::new(engine.contexto.lang, game_id);
-            engine.contexto = contexto.clone();
-            *self.contexto_api.lock().await = contexto;
+    /// start `game` over for session `id`, forgetting its guesses and solver progress
+    pub async fn reset_game(&self, id: &str, game: &GameId) -> AppResult<()> {
+        let mut session = self.open_game(id, game).await?;
+        self.sqlite
+            .delete_game(id, &game.lang().to_string(), &game.key())
+            .await?;
 
-            // db
-            self.sqlite.delete_all_guesses().await?;
+        session.engine.reset();
+        session.suggestion = random_suggestion(&session.engine).await?;
+        info!("session {} reset game {:?}", id, game);
+        Ok(())
+    }
 
-            // & new random suggestion
-            {
-                let random_vec = engine.generate_seed(1).await?;
-                let mut s = self.suggestion.lock().await;
-                *s = engine.qdrant.get_word(random_vec).await?;
+    /// Run a fresh solver on `game` in the background for session `id` to watch,
+    /// stopping the run it was watching before. Returns the id to `attach_watch` with;
+    /// the run is stopped if nothing attaches to it within `--watch-attach-secs`.
+    ///
+    /// Its guesses go through the same api as the player's, but aren't recorded
+    pub async fn start_watch(&self, id: &str, game: &GameId) -> AppResult<String> {
+        let mut session = self.open_game(id, game).await?;
+        let language = self.language(game.lang()).await?;
+
+        let run = WatchRun::start(
+            language.solver(session.engine.oracle.clone()),
+            self.watch_grace,
+        );
+        let run_id = run.id.clone();
+        if let Some(previous) = session.watch.replace(run) {
+            previous.stop();
+        }
+        info!("session {} started watch run {} on game {:?}", id, run_id, game);
+        Ok(run_id)
+    }
+
+    /// events of run `run_id` of session `id`, none if it's over, already watched or
+    /// was replaced by another run
+    pub async fn attach_watch(
+        &self,
+        id: &str,
+        game: &GameId,
+        run_id: &str,
+    ) -> AppResult<Option<Watch>> {
+        let mut session = self.open_game(id, game).await?;
+        Ok(session
+            .watch
+            .as_mut()
+            .filter(|run| run.id == run_id)
+            .and_then(|run| run.attach()))
+    }
+
+    /// cancel the solver run session `id` is watching, if any
+    pub async fn stop_watch(&self, id: &str, game: &GameId) -> AppResult<()> {
+        let session = self.open_game(id, game).await?;
+        if let Some(run) = session.watch.as_ref() {
+            run.stop();
+        }
+        Ok(())
+    }
+
+    /// switch `session` to `game` if it isn't the one being played
+    async fn maybe_reset(&self, session: &mut Session, game: &GameId) -> AppResult<()> {
+        if session.game.as_ref() != Some(game) {
+            info!("session {} switching to game {:?}", session.id, game);
+
+            // api
+            // clone to keep sharing the response cache
+            let language = self.language(game.lang()).await?;
+            let oracle = match game {
+                GameId::Contexto(_, game_id) => {
+                    let mut contexto = language.contexto.clone();
+                    contexto.game_id = *game_id;
+                    GameOracle::Contexto(contexto)
+                }
+                GameId::Puzzle(lang, id) => GameOracle::Puzzle {
+                    lang: *lang,
+                    id: id.clone(),
+                    oracle: self.puzzle_oracle(&language, id).await?,
+                },
+            };
+
+            // solver, over the vocabulary of the game's language
+            let engine = &mut session.engine;
+            if session.lang == game.lang() {
+                engine.reset();
+                engine.oracle = oracle;
+            } else {
+                *engine = language.solver(oracle);
+                session.lang = game.lang();
+            }
+
+            // resume the new game if it was played before
+            if let Some(state) = self.load_checkpoint(&session.id, game).await? {
+                engine.restore(state);
             }
+
+            // & new random suggestion
+            session.suggestion = random_suggestion(engine).await?;
+            session.game = Some(game.clone());
         }
         Ok(())
     }
 
-    pub async fn play(&self, word: &str) -> Result<u32> {
-        self.contexto_api
-            .lock()
-            .await
-            .play(&word)
-            .await
-            .map_err(|e| anyhow::anyhow!("failed to play turn with contexto\n{:?}", e))
+    /// ranking of puzzle `id`, computed once and shared by every session playing it
+    async fn puzzle_oracle(&self, language: &Language, id: &str) -> AppResult<Arc<LocalOracle>> {
+        if let Some(oracle) = language.puzzles.lock().await.get(id) {
+            return Ok(oracle);
+        }
+
+        // ranked without holding the lock, other puzzles stay playable meanwhile
+        let puzzle = self
+            .sqlite
+            .load_puzzle(id)
+            .await?
+            .filter(|p| p.lang == language.config.lang.to_string())
+            .ok_or_else(|| AppError::PuzzleNotFound(id.to_owned()))?;
+        let oracle = Arc::new(LocalOracle::new(language.store.as_ref(), &puzzle.target).await?);
+
+        // every ranking holds the whole vocabulary, only keep a few around
+        Ok(language.puzzles.lock().await.get_or_insert(id, oracle))
+    }
+
+    async fn play(
+        &self,
+        session: &Session,
+        word: &str,
+    ) -> konteksto_engine::errors::Result<Played> {
+        session.engine.oracle.play(word).await
+    }
+
+    /// host a new puzzle ranked against `target`, or a random word of the collection,
+    /// and return its id
+    pub async fn create_puzzle(&self, lang: Lang, target: Option<String>) -> AppResult<String> {
+        let language = self.language(lang).await?;
+        let store = &language.store;
+        let target = match target.map(|t| t.trim().to_lowercase()) {
+            Some(target) if !target.is_empty() => {
+                if store.get_embedding(target.clone()).await.is_none() {
+                    return Err(KontekstoError::UnknownWord(target).into());
+                }
+                target
+            }
+            _ => {
+                let random_vec = store.get_random_vecs(1).await?.pop();
+                let random_vec = random_vec
+                    .ok_or_else(|| KontekstoError::EmptyCollection(store.collection().to_owned()))?;
+                store.get_word(random_vec).await?
+            }
+        };
+
+        // short enough to share, and doesn't give the answer away
+        let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let puzzle = Puzzle {
+            id: id.clone(),
+            lang: lang.to_string(),
+            target,
+        };
+        self.sqlite.create_puzzle(&puzzle).await?;
+        info!("created puzzle {}", id);
+
+        Ok(id)
     }
 
     /// Manually step the engine and generate a new suggestion
     /// variant of algo in konteksto-engine/solver.rs
-    pub async fn notify_solver(&self, word: String) -> Result<()> {
-        let solver = &mut self.engine.lock().await;
+    ///
+    /// The suggestion is left untouched when stepping fails
+    async fn notify_solver(
+        &self,
+        session: &mut Session,
+        word: String,
+        lemma: String,
+    ) -> Result<()> {
+        let solver = &mut session.engine;
 
-        let embed = match solver.qdrant.get_embedding(word).await {
+        // other forms of the lemma won't be suggested
+        solver.observe_lemma(lemma);
+
+        // contexto knows words outside of our vocabulary, the solver can't use those
+        let embed = match solver.store.get_embedding(word.clone()).await {
             Some(v) => v,
-            None => anyhow::bail!("word not found"),
+            None => {
+                info!("{}", KontekstoError::MissingEmbedding(word));
+                return Ok(());
+            }
         };
 
         let prev_best = solver.current_best();
@@ -99,24 +542,57 @@ impl InnerState {
                 info!("best: {:?}, attempt: {:?}", &prev_best, &attempt);
 
                 // if no change re-use a word near the local min
-                let best_query = if attempt.1 > prev_best.1 {
-                    solver.qdrant.get_embedding(prev_best.0).await.unwrap()
-                } else {
-                    next_query
+                let best_query = match attempt.1 > prev_best.1 {
+                    true => solver.store.get_embedding(prev_best.0).await,
+                    false => None,
                 };
 
-                let mut nearest_neighbors = solver.query_unseen(best_query, 1).await?;
-                nearest_neighbors.remove(0).word
+                let nearest_neighbors = solver
+                    .query_unseen(best_query.unwrap_or(next_query), SUGGESTION_POOL)
+                    .await?;
+                match nearest_neighbors
+                    .into_iter()
+                    .find(|e| !solver.seen_lemma(&e.word))
+                {
+                    Some(entry) => entry.word,
+                    None => session.suggestion.clone(),
+                }
             }
             Step::Done => solver.current_best().0,
-            _ => unreachable!(),
+            // out of iterations, keep the last suggestion
+            Step::Bailed(_) => session.suggestion.clone(),
         };
+        session.suggestion = suggestion;
 
-        {
-            let mut s = self.suggestion.lock().await;
-            *s = suggestion;
-        }
+        let (state, game) = (session.engine.snapshot(), session.engine.oracle.game());
+        self.save_checkpoint(&session.id, &state, &game).await
+    }
 
+    /// persist the solver progress for the current game
+    async fn save_checkpoint(&self, session: &str, state: &SolverState, game: &GameId) -> Result<()> {
+        let state = serde_json::to_string(state)?;
+        self.sqlite
+            .save_checkpoint(session, &game.lang().to_string(), &game.key(), &state)
+            .await?;
         Ok(())
     }
+
+    async fn load_checkpoint(&self, session: &str, game: &GameId) -> Result<Option<SolverState>> {
+        let state = self
+            .sqlite
+            .load_checkpoint(session, &game.lang().to_string(), &game.key())
+            .await?;
+        Ok(state.map(|s| serde_json::from_str(&s)).transpose()?)
+    }
+}
+
+/// unseen word near a random seed, skipping words contexto is known to refuse
+async fn random_suggestion(engine: &AnySolver<GameOracle>) -> Result<String> {
+    let random_vec = engine.generate_seed(1).await?;
+    let unseen = engine.query_unseen(random_vec.clone(), SUGGESTION_POOL).await?;
+    let word = match unseen.into_iter().find(|e| !engine.seen_lemma(&e.word)) {
+        Some(entry) => entry.word,
+        None => engine.store.get_word(random_vec).await?,
+    };
+    Ok(word)
 }
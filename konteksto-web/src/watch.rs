@@ -0,0 +1,135 @@
+use futures::{Stream, stream};
+use konteksto_engine::solver::{
+    AnySolver, Attempt, SolveEvent, solve_with_restarts_observed,
+};
+use std::time::Duration;
+use tokio::{
+    sync::{
+        mpsc::{UnboundedReceiver, unbounded_channel},
+        oneshot,
+    },
+    task::AbortHandle,
+    time::timeout,
+};
+use tracing::info;
+
+use crate::game::GameOracle;
+
+/// progress of a solver run watched by a player
+#[derive(Debug)]
+pub enum WatchEvent {
+    Solve(SolveEvent),
+    /// the run is over, with the best word found
+    Done(Attempt),
+    /// the run was stopped, or failed, before the end
+    Stopped,
+}
+
+/// A solver run a player started, which a single event stream can attach to.
+///
+/// Runs are only ever started explicitly: a stream reconnecting to a run which is over,
+/// or already watched, gets a `Stopped` event rather than a new run. A run no stream
+/// attaches to within its grace period is stopped, so a player leaving the page before
+/// the stream opens doesn't leave it solving.
+pub struct WatchRun {
+    pub id: String,
+    /// handed to the first stream attaching to the run
+    watch: Option<Watch>,
+    /// tells the grace period the run was attached to
+    attached: Option<oneshot::Sender<()>>,
+    run: AbortHandle,
+}
+
+impl WatchRun {
+    pub fn start(solver: AnySolver<GameOracle>, grace: Duration) -> Self {
+        let watch = Watch::start(solver);
+        let run = watch.run.clone();
+
+        let (attached, on_attach) = oneshot::channel();
+        let unwatched = run.clone();
+        tokio::spawn(async move {
+            if !matches!(timeout(grace, on_attach).await, Ok(Ok(()))) {
+                info!("stopping watch run no stream attached to");
+                unwatched.abort();
+            }
+        });
+
+        Self {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            watch: Some(watch),
+            attached: Some(attached),
+            run,
+        }
+    }
+
+    /// events of the run, only the first time it's attached to
+    pub fn attach(&mut self) -> Option<Watch> {
+        let watch = self.watch.take()?;
+        if let Some(attached) = self.attached.take() {
+            let _ = attached.send(());
+        }
+        Some(watch)
+    }
+
+    pub fn stop(&self) {
+        self.run.abort();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.run.is_finished()
+    }
+}
+
+/// Receiving end of a solver run in the background.
+///
+/// The run is cancelled once this is dropped, e.g. when the player leaves the page.
+pub struct Watch {
+    events: UnboundedReceiver<WatchEvent>,
+    run: AbortHandle,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        self.run.abort();
+    }
+}
+
+impl Watch {
+    /// solve with restarts in the background, as the cli does
+    fn start(mut solver: AnySolver<GameOracle>) -> Self {
+        let (tx, events) = unbounded_channel();
+
+        let run = tokio::spawn(async move {
+            let restarts = solver.settings().max_retries;
+            let seeds = match solver.generate_seeds(restarts).await {
+                Ok(seeds) => seeds,
+                Err(e) => {
+                    info!("failed to seed watched solver: {}", e);
+                    return;
+                }
+            };
+            let best = solve_with_restarts_observed(&mut solver, seeds, |event| {
+                let _ = tx.send(WatchEvent::Solve(event));
+            })
+            .await;
+            let _ = tx.send(WatchEvent::Done(best));
+        });
+
+        Self {
+            events,
+            run: run.abort_handle(),
+        }
+    }
+
+    /// events of the run, ending with `Done` or, if it was cut short, `Stopped`
+    pub fn into_stream(self) -> impl Stream<Item = WatchEvent> {
+        stream::unfold(Some(self), |watch| async move {
+            let mut watch = watch?;
+            match watch.events.recv().await {
+                Some(WatchEvent::Done(best)) => Some((WatchEvent::Done(best), None)),
+                Some(event) => Some((event, Some(watch))),
+                None => Some((WatchEvent::Stopped, None)),
+            }
+        })
+    }
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::{ANSWER, TestApp};
+use http::StatusCode;
+
+const GUESSES: &str = "/api/v1/en/games/1/guesses";
+
+/// status of the reply and its `error`, which every failure must carry as json
+fn error(reply: &common::Reply) -> (StatusCode, String) {
+    let error = reply.json()["error"]
+        .as_str()
+        .unwrap_or_else(|| panic!("no error in '{}'", reply.body))
+        .to_owned();
+    (reply.status, error)
+}
+
+#[tokio::test]
+async fn guesses_are_scored_and_listed_in_order() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    let reply = player.post_json(GUESSES, r#"{"word": "w100"}"#).await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+    assert_eq!(reply.json()["turn"], 1);
+
+    let reply = player.post_json(GUESSES, &format!(r#"{{"word": "{}"}}"#, ANSWER)).await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+    assert_eq!(reply.json()["distance"], 0);
+
+    let reply = player.get(GUESSES).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let words: Vec<_> = reply.json().as_array().unwrap().iter().map(|g| g["word"].clone()).collect();
+    assert_eq!(words, ["w100", ANSWER]);
+}
+
+#[tokio::test]
+async fn unknown_languages_are_json_404s() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    // not a language at all
+    let (status, message) = error(&player.get("/api/v1/xx/games/1/guesses").await);
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(message, "language 'xx' isn't served here");
+
+    // a language this server doesn't serve
+    let (status, message) = error(&player.get("/api/v1/es/games/1/guesses").await);
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(message, "language 'es' isn't served here");
+}
+
+#[tokio::test]
+async fn malformed_requests_are_json_400s() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    let (status, _) = error(&player.post_json(GUESSES, "{not json").await);
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = error(&player.post_json(GUESSES, r#"{"guess": "w100"}"#).await);
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, message) = error(&player.get("/api/v1/en/games/nope/guesses").await);
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(message, "'nope' isn't a game id nor a puzzle-<id>");
+
+    let reply = player.get("/api/v1/en/games/1/suggestions?limit=many").await;
+    assert_eq!(error(&reply).0, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn refused_and_repeated_guesses_are_reported() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    let (status, _) = error(&player.post_json(GUESSES, r#"{"word": "zzz"}"#).await);
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let reply = player.post_json(GUESSES, r#"{"word": "w100"}"#).await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+    let (status, message) = error(&player.post_json(GUESSES, r#"{"word": "w100"}"#).await);
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(message, "'w100' was guessed already");
+
+    let (status, _) = error(&player.get("/api/v1/en/games/puzzle-nope/solver").await);
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn each_language_plays_with_its_own_optimizer_file() {
+    let dir = std::env::temp_dir().join(format!("konteksto-optimizer-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut tuned = common::args(&[]).optimizer_config;
+    tuned.max_iters = 7;
+    tuned.to_file(&dir.join("en.json").to_string_lossy()).unwrap();
+
+    let file = dir.join("{lang}.json").to_string_lossy().to_string();
+    let app = TestApp::spawn(&["--optimizer-file", &file]).await;
+    let reply = app.player().get("/api/v1/en/games/1/solver").await;
+    assert_eq!(reply.status, StatusCode::OK, "{}", reply.body);
+    assert_eq!(reply.json()["settings"]["max_iters"], 7);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
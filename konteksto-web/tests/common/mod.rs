@@ -0,0 +1,207 @@
+#![allow(dead_code)]
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use clap::Parser;
+use http::{HeaderMap, StatusCode, header::COOKIE, header::SET_COOKIE};
+use konteksto_engine::{
+    Args, MemoryStore,
+    clients::{Entry, VectorStore},
+    config::Lang,
+};
+use konteksto_web::{
+    config::Settings,
+    mock::MockContexto,
+    routes::get_routes,
+    server::router,
+    session::SESSION_COOKIE,
+    state::{AppState, InnerState},
+};
+use tokio::net::TcpListener;
+
+/// answer of game 1 of the mock contexto api
+pub const ANSWER: &str = "w250";
+/// answer of game 2 of the mock contexto api
+pub const OTHER_ANSWER: &str = "w400";
+
+/// words spread evenly over a sphere, so every region of it is reachable by small moves
+pub fn sphere(n: usize) -> Vec<Entry> {
+    let golden = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+    (0..n)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / n as f32;
+            let r = (1.0 - y * y).sqrt();
+            let theta = golden * i as f32;
+            Entry {
+                word: format!("w{:03}", i),
+                embedding: vec![r * theta.cos(), y, r * theta.sin()],
+            }
+        })
+        .collect()
+}
+
+/// engine settings as parsed from `flags`
+pub fn args(flags: &[&str]) -> Args {
+    Args::parse_from(std::iter::once("web").chain(flags.iter().copied()))
+}
+
+/// serve `router` on a free local port, returning its url
+async fn serve(router: axum::Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    url
+}
+
+/// The web app over an in-memory english collection, playing against a `mock-contexto`
+/// whose games 1 and 2 are `ANSWER` and `OTHER_ANSWER`. Everything it writes goes to a temporary directory
+pub struct TestApp {
+    pub url: String,
+    pub state: AppState,
+    dir: PathBuf,
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+impl TestApp {
+    /// app started with the server `flags` on top of the test settings
+    pub async fn spawn(flags: &[&str]) -> Self {
+        let dir = std::env::temp_dir().join(format!("konteksto-web-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let store: Arc<dyn VectorStore> = Arc::new(MemoryStore::new("en", sphere(500)).unwrap());
+        let stores = HashMap::from([(Lang::En, store)]);
+
+        let games = HashMap::from([(
+            Lang::En,
+            HashMap::from([(1, ANSWER.to_owned()), (2, OTHER_ANSWER.to_owned())]),
+        )]);
+        let mock_url = serve(MockContexto::new(games, stores.clone(), 100).router()).await;
+
+        let db = dir.join("app.db").to_string_lossy().to_string();
+        let rejects = dir.join("rejects").to_string_lossy().to_string();
+        let mut args = vec![
+            "web",
+            "--sqlite-db",
+            &db,
+            "--rejects-dir",
+            &rejects,
+            "--contexto-url",
+            &mock_url,
+            "--no-cache",
+            "--rate-limit",
+            "0",
+            "--lang",
+            "en",
+            "--langs",
+            "en",
+            "--seed",
+            "7",
+        ];
+        args.extend_from_slice(flags);
+        let settings = Settings::parse_from(args);
+
+        let state = InnerState::with_stores(&settings, stores).await.unwrap();
+        let state = AppState(Arc::new(state));
+        let url = serve(router(get_routes(), state.clone())).await;
+
+        Self { url, state, dir }
+    }
+
+    /// a new browser, without any cookie yet
+    pub fn player(&self) -> Player {
+        Player {
+            url: self.url.clone(),
+            http: reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .unwrap(),
+            cookie: None,
+        }
+    }
+}
+
+/// a browser keeping the session cookie it was handed
+pub struct Player {
+    url: String,
+    http: reqwest::Client,
+    pub cookie: Option<String>,
+}
+
+/// response status, headers and body
+pub struct Reply {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl Reply {
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body)
+            .unwrap_or_else(|e| panic!("'{}' isn't json: {}", self.body, e))
+    }
+
+    /// session id handed out with the response, if any
+    pub fn session(&self) -> Option<String> {
+        self.headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|h| h.to_str().ok())
+            .filter_map(|c| c.split(';').next()?.split_once('='))
+            .find(|(name, _)| *name == SESSION_COOKIE)
+            .map(|(_, id)| id.to_owned())
+    }
+}
+
+impl Player {
+    pub async fn send(&mut self, req: reqwest::RequestBuilder) -> Reply {
+        let req = match &self.cookie {
+            Some(id) => req.header(COOKIE, format!("{}={}", SESSION_COOKIE, id)),
+            None => req,
+        };
+        let res = req.send().await.unwrap();
+        let reply = Reply {
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: res.text().await.unwrap(),
+        };
+        if let Some(id) = reply.session() {
+            self.cookie = Some(id);
+        }
+        reply
+    }
+
+    /// request to `path`, to be sent with `send`
+    pub fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.http.request(method, format!("{}{}", self.url, path))
+    }
+
+    pub async fn get(&mut self, path: &str) -> Reply {
+        self.send(self.request(reqwest::Method::GET, path)).await
+    }
+
+    pub async fn post_form(&mut self, path: &str, form: &[(&str, &str)]) -> Reply {
+        let body = form
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&");
+        let req = self
+            .request(reqwest::Method::POST, path)
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(body);
+        self.send(req).await
+    }
+
+    pub async fn post_json(&mut self, path: &str, body: &str) -> Reply {
+        let req = self
+            .request(reqwest::Method::POST, path)
+            .header("content-type", "application/json")
+            .body(body.to_owned());
+        self.send(req).await
+    }
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::{OTHER_ANSWER, Player, TestApp};
+use http::StatusCode;
+
+/// guesses of game `game`
+fn guesses(game: &str) -> String {
+    format!("/api/v1/en/games/{}/guesses", game)
+}
+
+/// words and turns of the guesses `player` sees in `game`
+async fn listed(player: &mut Player, game: &str) -> Vec<(String, u64)> {
+    let reply = player.get(&guesses(game)).await;
+    assert_eq!(reply.status, StatusCode::OK, "{}", reply.body);
+    reply
+        .json()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|g| {
+            (
+                g["word"].as_str().unwrap().to_owned(),
+                g["turn"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+async fn guess(player: &mut Player, game: &str, word: &str) -> u64 {
+    let reply = player
+        .post_json(&guesses(game), &format!(r#"{{"word": "{}"}}"#, word))
+        .await;
+    assert_eq!(reply.status, StatusCode::CREATED, "{}", reply.body);
+    reply.json()["turn"].as_u64().unwrap()
+}
+
+#[tokio::test]
+async fn guesses_are_kept_per_game_in_the_order_they_were_made() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    assert_eq!(guess(&mut player, "1", "w300").await, 1);
+    assert_eq!(
+        guess(&mut player, "2", "w100").await,
+        1,
+        "turns count per game"
+    );
+    assert_eq!(guess(&mut player, "1", "w100").await, 2);
+    assert_eq!(guess(&mut player, "2", OTHER_ANSWER).await, 2);
+
+    // w100 counted in both games, but only once in each
+    let reply = player.post_json(&guesses("1"), r#"{"word": "w100"}"#).await;
+    assert_eq!(reply.status, StatusCode::CONFLICT);
+
+    assert_eq!(
+        listed(&mut player, "1").await,
+        [("w300".to_owned(), 1), ("w100".to_owned(), 2)]
+    );
+    assert_eq!(
+        listed(&mut player, "2").await,
+        [("w100".to_owned(), 1), (OTHER_ANSWER.to_owned(), 2)]
+    );
+}
+
+#[tokio::test]
+async fn played_games_are_remembered_with_their_best_guess() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    guess(&mut player, "1", "w100").await;
+    guess(&mut player, "2", "w100").await;
+    guess(&mut player, "2", OTHER_ANSWER).await;
+
+    let id = player.cookie.clone().unwrap();
+    let mut history: Vec<_> = app
+        .state
+        .sqlite
+        .played_games(&id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|g| (g.lang, g.game, g.guesses, g.best))
+        .collect();
+    history.sort();
+    assert_eq!(history.len(), 2);
+    assert_eq!((history[0].1.as_str(), history[0].2), ("1", 1));
+    assert_eq!(
+        (history[1].1.as_str(), history[1].2, history[1].3),
+        ("2", 2, 0),
+        "game 2 was won"
+    );
+}
+
+#[tokio::test]
+async fn starting_a_game_over_leaves_the_others_alone() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    guess(&mut player, "1", "w100").await;
+    guess(&mut player, "2", "w200").await;
+
+    let reply = player
+        .send(player.request(reqwest::Method::DELETE, "/api/v1/en/games/1"))
+        .await;
+    assert!(reply.status.is_success(), "{}", reply.body);
+
+    assert!(listed(&mut player, "1").await.is_empty());
+    assert_eq!(listed(&mut player, "2").await, [("w200".to_owned(), 1)]);
+    // and the first guess of the new round is turn 1 again
+    assert_eq!(guess(&mut player, "1", "w100").await, 1);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{Player, TestApp};
+use http::{StatusCode, header::LOCATION};
+
+/// ask for a puzzle with the creation `form`
+async fn create(player: &mut Player, form: &[(&str, &str)]) -> common::Reply {
+    player.post_form("/api/en/puzzle", form).await
+}
+
+/// id of the puzzle a successful `create` redirected to
+fn puzzle_id(reply: &common::Reply) -> String {
+    assert!(
+        reply.status.is_redirection(),
+        "{}: {}",
+        reply.status,
+        reply.body
+    );
+    let location = reply.headers[LOCATION].to_str().unwrap();
+    let id = location
+        .strip_prefix("/en/puzzle/")
+        .unwrap()
+        .trim_end_matches('/');
+    id.to_owned()
+}
+
+#[tokio::test]
+async fn puzzles_are_disabled_unless_allowed() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    let reply = create(&mut player, &[("target", "w100")]).await;
+    assert_eq!(reply.status, StatusCode::FORBIDDEN);
+
+    let reply = player.get("/en/puzzle/new").await;
+    assert!(
+        reply
+            .body
+            .contains("Puzzles can't be created on this server.")
+    );
+}
+
+#[tokio::test]
+async fn the_admin_token_gates_puzzle_creation() {
+    let app = TestApp::spawn(&["--allow-puzzles", "--admin-token", "s3cret"]).await;
+    let mut player = app.player();
+
+    let reply = create(&mut player, &[("target", "w100")]).await;
+    assert_eq!(reply.status, StatusCode::FORBIDDEN, "no token");
+    let reply = create(&mut player, &[("target", "w100"), ("token", "guess")]).await;
+    assert_eq!(reply.status, StatusCode::FORBIDDEN, "wrong token");
+
+    let reply = create(&mut player, &[("target", "w100"), ("token", "s3cret")]).await;
+    puzzle_id(&reply);
+}
+
+#[tokio::test]
+async fn puzzles_are_played_against_their_target() {
+    let app = TestApp::spawn(&["--allow-puzzles"]).await;
+    let mut player = app.player();
+
+    let reply = create(&mut player, &[("target", "zzz")]).await;
+    assert_eq!(
+        reply.status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "unknown target"
+    );
+
+    let id = puzzle_id(&create(&mut player, &[("target", "w100")]).await);
+    let page = player.get(&format!("/en/puzzle/{}/", id)).await;
+    assert_eq!(page.status, StatusCode::OK);
+
+    let guesses = format!("/api/v1/en/games/puzzle-{}/guesses", id);
+    let reply = player.post_json(&guesses, r#"{"word": "w300"}"#).await;
+    assert_eq!(reply.status, StatusCode::CREATED, "{}", reply.body);
+    assert!(reply.json()["distance"].as_u64().unwrap() > 0);
+    let reply = player.post_json(&guesses, r#"{"word": "w100"}"#).await;
+    assert_eq!(reply.json()["distance"], 0);
+
+    // random targets when none is given
+    puzzle_id(&create(&mut player, &[]).await);
+}
+
+#[tokio::test]
+async fn unknown_puzzles_are_404s() {
+    let app = TestApp::spawn(&["--allow-puzzles"]).await;
+    let mut player = app.player();
+
+    let reply = player.get("/api/v1/en/games/puzzle-nope/guesses").await;
+    assert_eq!(reply.status, StatusCode::NOT_FOUND);
+    let reply = player.get("/en/puzzle/nope/").await;
+    assert_eq!(reply.status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::TestApp;
+use http::StatusCode;
+
+const GUESSES: &str = "/api/v1/en/games/1/guesses";
+
+/// words of the guesses `player` sees in game 1
+async fn words(player: &mut common::Player) -> Vec<String> {
+    let reply = player.get(GUESSES).await;
+    assert_eq!(reply.status, StatusCode::OK, "{}", reply.body);
+    reply
+        .json()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|g| g["word"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn sessions_are_issued_then_saved_with_their_first_guess() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    let reply = player.get("/en/game/1/").await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let id = reply.session().expect("a session is handed out");
+    assert!(!app.state.sqlite.session_exists(&id).await.unwrap());
+
+    player.post_json(GUESSES, r#"{"word": "w100"}"#).await;
+    assert!(app.state.sqlite.session_exists(&id).await.unwrap());
+}
+
+#[tokio::test]
+async fn clients_which_never_play_leave_nothing_behind() {
+    let app = TestApp::spawn(&[]).await;
+
+    for _ in 0..3 {
+        let reply = app.player().get("/api/v1/openapi.json").await;
+        assert_eq!(reply.status, StatusCode::OK);
+        let id = reply.session().expect("a session is handed out");
+        assert!(!app.state.sqlite.session_exists(&id).await.unwrap());
+    }
+}
+
+#[tokio::test]
+async fn sessions_are_reused_through_their_cookie() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    player.post_json(GUESSES, r#"{"word": "w100"}"#).await;
+    let reply = player.get("/en/game/1/").await;
+    assert_eq!(reply.session(), None, "no new session for a known cookie");
+    assert_eq!(words(&mut player).await, ["w100"]);
+}
+
+#[tokio::test]
+async fn forged_cookies_get_a_fresh_session() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+    player.post_json(GUESSES, r#"{"word": "w100"}"#).await;
+
+    let mut forger = app.player();
+    let forged = "0123456789abcdef0123456789abcdef".to_owned();
+    forger.cookie = Some(forged.clone());
+    let reply = forger.get("/en/game/1/").await;
+    let issued = reply.session().expect("the forged id is replaced");
+    assert_ne!(issued, forged);
+
+    // malformed ids are replaced as well
+    forger.cookie = Some("not-a-session".to_owned());
+    assert!(forger.get("/en/game/1/").await.session().is_some());
+    assert!(words(&mut forger).await.is_empty());
+}
+
+#[tokio::test]
+async fn players_only_see_their_own_guesses() {
+    let app = TestApp::spawn(&[]).await;
+    let (mut alice, mut bob) = (app.player(), app.player());
+
+    alice.post_json(GUESSES, r#"{"word": "w100"}"#).await;
+    bob.post_json(GUESSES, r#"{"word": "w200"}"#).await;
+    bob.post_json(GUESSES, r#"{"word": "w300"}"#).await;
+
+    assert_eq!(words(&mut alice).await, ["w100"]);
+    assert_eq!(words(&mut bob).await, ["w200", "w300"]);
+}
+
+#[tokio::test]
+async fn idle_sessions_make_room_and_pick_up_where_they_left_off() {
+    let app = TestApp::spawn(&["--max-sessions", "1", "--session-idle-secs", "0"]).await;
+    let (mut alice, mut bob) = (app.player(), app.player());
+
+    alice.post_json(GUESSES, r#"{"word": "w100"}"#).await;
+    // alice is idle right away, bob takes her slot
+    let reply = bob.post_json(GUESSES, r#"{"word": "w200"}"#).await;
+    assert_eq!(reply.status, StatusCode::CREATED);
+
+    assert_eq!(words(&mut alice).await, ["w100"]);
+}
+
+#[tokio::test]
+async fn active_sessions_are_not_evicted() {
+    let app = TestApp::spawn(&["--max-sessions", "1", "--session-idle-secs", "3600"]).await;
+    let (mut alice, mut bob) = (app.player(), app.player());
+
+    alice.post_json(GUESSES, r#"{"word": "w100"}"#).await;
+    let reply = bob.post_json(GUESSES, r#"{"word": "w200"}"#).await;
+    assert_eq!(reply.status, StatusCode::SERVICE_UNAVAILABLE);
+
+    assert_eq!(words(&mut alice).await, ["w100"]);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{Player, TestApp};
+use http::StatusCode;
+
+/// start a solver run on game 1, returning the url of its event stream
+async fn start(player: &mut Player) -> String {
+    let reply = player.post_form("/api/en/game/1/watch", &[]).await;
+    assert_eq!(reply.status, StatusCode::OK, "{}", reply.body);
+    let (_, rest) = reply.body.split_once("sse-connect=\"").unwrap();
+    rest.split_once('"').unwrap().0.to_owned()
+}
+
+/// names of the events of the stream at `url`, read until it ends, and its last event
+async fn attach(player: &mut Player, url: &str) -> (Vec<String>, String) {
+    let reply = player.get(url).await;
+    assert_eq!(reply.status, StatusCode::OK, "{}", reply.body);
+    let events: Vec<_> = reply
+        .body
+        .lines()
+        .filter_map(|l| l.strip_prefix("event: "))
+        .map(str::to_owned)
+        .collect();
+    let (_, last) = reply.body.rsplit_once("event: ").unwrap();
+    (events, last.to_owned())
+}
+
+#[tokio::test]
+async fn started_runs_are_streamed_to_the_end_once() {
+    let app = TestApp::spawn(&[]).await;
+    let mut player = app.player();
+
+    let url = start(&mut player).await;
+    let (events, last) = attach(&mut player, &url).await;
+    assert!(events.iter().any(|e| e == "guess"), "{:?}", events);
+    assert_eq!(events.last().unwrap(), "done");
+    assert!(!last.contains("stopped"), "{}", last);
+
+    // reconnecting doesn't start another run
+    let (events, last) = attach(&mut player, &url).await;
+    assert_eq!(events, ["done"]);
+    assert!(last.contains("stopped"), "{}", last);
+}
+
+#[tokio::test]
+async fn runs_nobody_attaches_to_are_stopped() {
+    let app = TestApp::spawn(&["--watch-attach-secs", "0"]).await;
+    let mut player = app.player();
+
+    let url = start(&mut player).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // whatever it got through before the grace period ran out, the run didn't finish
+    let (events, last) = attach(&mut player, &url).await;
+    assert_eq!(events.last().unwrap(), "done");
+    assert!(last.contains("stopped"), "{}", last);
+}
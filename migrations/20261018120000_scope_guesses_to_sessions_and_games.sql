@@ -0,0 +1,45 @@
+-- guesses and solver checkpoints belong to the browser session that made them and
+-- are kept per game, so players can go back to an earlier one; `game` is a contexto
+-- game id or `puzzle-<id>`.
+--
+-- Guesses made before sessions existed belonged to the single shared player and
+-- weren't tagged with the game they were made in, so no session or game can claim
+-- them: they are dropped here.
+DROP TABLE guesses;
+CREATE TABLE guesses(
+  session TEXT NOT NULL,
+  lang TEXT NOT NULL,
+  game TEXT NOT NULL,
+  word TEXT NOT NULL,
+  score int,
+  -- form contexto normalized the guess to
+  lemma TEXT,
+  -- 1 for the first guess of the game
+  turn INTEGER NOT NULL,
+  -- unix seconds
+  created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+  PRIMARY KEY (session, lang, game, word)
+);
+
+-- solver progress per game, restored when the server restarts
+CREATE TABLE checkpoints(
+  session TEXT NOT NULL,
+  lang TEXT NOT NULL,
+  game TEXT NOT NULL,
+  state TEXT NOT NULL,
+  PRIMARY KEY (session, lang, game)
+);
+
+-- session ids handed out by the server, cookies with any other id get a new one
+CREATE TABLE sessions(
+  id TEXT PRIMARY KEY NOT NULL,
+  created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+-- custom puzzles ranked locally against a target word, shared by id
+CREATE TABLE puzzles(
+  id TEXT PRIMARY KEY NOT NULL,
+  lang TEXT NOT NULL,
+  target TEXT NOT NULL,
+  created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);